@@ -0,0 +1,318 @@
+//! Derive macros for [`arcstr`](https://docs.rs/arcstr).
+//!
+//! Don't depend on this crate directly, instead enable the `derive` feature of
+//! `arcstr`, and use the macros from there (e.g. `arcstr::ArcStrEnum`).
+
+extern crate proc_macro;
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Error, Fields, LitStr};
+
+/// Derive `ArcStr` conversions for a field-less enum, using the variant names.
+///
+/// This generates:
+///
+/// - An inherent `const fn as_arcstr(&self) -> ArcStr`, which returns a static
+///   `ArcStr` (as with `literal_arcstr!`) holding the variant's name. This
+///   never allocates, and the returned `ArcStr` is free to clone and drop.
+///
+/// - `From<Enum> for ArcStr`, which does the same thing.
+///
+/// - `FromStr` and `TryFrom<&str>`, which parse the name back into the
+///   variant, failing with `arcstr::ParseVariantError` for anything else.
+///
+/// - If `#[arcstr(serde)]` is present (requires the `serde` feature of
+///   `arcstr`), `Serialize` and `Deserialize`, which use the name as a string.
+///
+/// By default the name of each variant is the same as its identifier. This
+/// can be changed with `#[arcstr(rename = "...")]` on a variant, or with
+/// `#[arcstr(rename_all = "...")]` on the enum, which accepts the same rules as
+/// serde: `"lowercase"`, `"UPPERCASE"`, `"PascalCase"`, `"camelCase"`,
+/// `"snake_case"`, `"SCREAMING_SNAKE_CASE"`, `"kebab-case"`, and
+/// `"SCREAMING-KEBAB-CASE"`.
+///
+/// # Example
+///
+/// ```
+/// use arcstr::{ArcStr, ArcStrEnum};
+///
+/// #[derive(ArcStrEnum, Debug, PartialEq)]
+/// #[arcstr(rename_all = "kebab-case", serde)]
+/// enum Event {
+///     PageView,
+///     Click,
+///     #[arcstr(rename = "error")]
+///     Failure,
+/// }
+///
+/// let name: ArcStr = Event::PageView.as_arcstr();
+/// assert_eq!(name, "page-view");
+/// assert!(ArcStr::is_static(&name));
+///
+/// assert_eq!("error".parse::<Event>().unwrap(), Event::Failure);
+/// assert!("Click".parse::<Event>().is_err());
+///
+/// assert_eq!(serde_json::to_string(&Event::Click).unwrap(), r#""click""#);
+/// ```
+#[proc_macro_derive(ArcStrEnum, attributes(arcstr))]
+pub fn derive_arcstr_enum(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match expand(&input) {
+        Ok(ts) => ts.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+struct EnumOpts {
+    rename_all: Option<RenameRule>,
+    serde: bool,
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let data = match &input.data {
+        Data::Enum(data) => data,
+        _ => {
+            return Err(Error::new_spanned(
+                &input.ident,
+                "`ArcStrEnum` can only be derived for enums",
+            ))
+        }
+    };
+    if !input.generics.params.is_empty() {
+        return Err(Error::new_spanned(
+            &input.generics,
+            "`ArcStrEnum` can't be derived for generic enums",
+        ));
+    }
+    let mut opts = EnumOpts {
+        rename_all: None,
+        serde: false,
+    };
+    for attr in input.attrs.iter().filter(|a| a.path().is_ident("arcstr")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("rename_all") {
+                let lit: LitStr = meta.value()?.parse()?;
+                opts.rename_all = Some(RenameRule::parse(&lit)?);
+                Ok(())
+            } else if meta.path.is_ident("serde") {
+                opts.serde = true;
+                Ok(())
+            } else {
+                Err(meta.error("unknown `arcstr` attribute"))
+            }
+        })?;
+    }
+
+    let mut idents = Vec::new();
+    let mut names: Vec<LitStr> = Vec::new();
+    for variant in &data.variants {
+        if !matches!(variant.fields, Fields::Unit) {
+            return Err(Error::new_spanned(
+                variant,
+                "`ArcStrEnum` only supports variants without fields",
+            ));
+        }
+        let mut rename = None;
+        for attr in variant.attrs.iter().filter(|a| a.path().is_ident("arcstr")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("rename") {
+                    rename = Some(meta.value()?.parse::<LitStr>()?);
+                    Ok(())
+                } else {
+                    Err(meta.error("unknown `arcstr` attribute"))
+                }
+            })?;
+        }
+        let name = match rename {
+            Some(lit) => lit,
+            None => {
+                let ident = variant.ident.to_string();
+                let ident = ident.strip_prefix("r#").unwrap_or(&ident);
+                let name = match opts.rename_all {
+                    Some(rule) => rule.apply(ident),
+                    None => ident.to_string(),
+                };
+                LitStr::new(&name, variant.ident.span())
+            }
+        };
+        if let Some(prev) = names.iter().find(|n| n.value() == name.value()) {
+            let mut e = Error::new(
+                name.span(),
+                format!("duplicate variant name {:?}", name.value()),
+            );
+            e.combine(Error::new(prev.span(), "previously used here"));
+            return Err(e);
+        }
+        idents.push(&variant.ident);
+        names.push(name);
+    }
+
+    let ty = &input.ident;
+    let ty_name = LitStr::new(&ty.to_string(), Span::call_site());
+    let as_arcstr_body = if idents.is_empty() {
+        quote!(match *self {})
+    } else {
+        quote! {
+            match self {
+                #(Self::#idents => {
+                    const NAME: ::arcstr::ArcStr = ::arcstr::literal_concat!(#names);
+                    NAME
+                })*
+            }
+        }
+    };
+    let mut out = quote! {
+        impl #ty {
+            /// Returns the name of this variant as a static `ArcStr`.
+            #[inline]
+            pub const fn as_arcstr(&self) -> ::arcstr::ArcStr {
+                #as_arcstr_body
+            }
+        }
+
+        impl ::core::convert::From<#ty> for ::arcstr::ArcStr {
+            #[inline]
+            fn from(v: #ty) -> ::arcstr::ArcStr {
+                v.as_arcstr()
+            }
+        }
+
+        impl ::core::str::FromStr for #ty {
+            type Err = ::arcstr::ParseVariantError;
+            fn from_str(s: &str) -> ::core::result::Result<Self, Self::Err> {
+                match s {
+                    #(#names => ::core::result::Result::Ok(Self::#idents),)*
+                    _ => ::core::result::Result::Err(
+                        ::arcstr::private_::parse_variant_error(#ty_name, s),
+                    ),
+                }
+            }
+        }
+
+        impl<'a> ::core::convert::TryFrom<&'a str> for #ty {
+            type Error = ::arcstr::ParseVariantError;
+            #[inline]
+            fn try_from(s: &'a str) -> ::core::result::Result<Self, Self::Error> {
+                <Self as ::core::str::FromStr>::from_str(s)
+            }
+        }
+    };
+    if opts.serde {
+        out.extend(expand_serde(ty, &ty_name, &names));
+    }
+    Ok(out)
+}
+
+fn expand_serde(ty: &syn::Ident, ty_name: &LitStr, names: &[LitStr]) -> TokenStream2 {
+    quote! {
+        const _: () = {
+            use ::arcstr::private_::serde as _serde;
+
+            impl _serde::Serialize for #ty {
+                fn serialize<S: _serde::Serializer>(
+                    &self,
+                    ser: S,
+                ) -> ::core::result::Result<S::Ok, S::Error> {
+                    ser.serialize_str(self.as_arcstr().as_str())
+                }
+            }
+
+            impl<'de> _serde::Deserialize<'de> for #ty {
+                fn deserialize<D: _serde::Deserializer<'de>>(
+                    d: D,
+                ) -> ::core::result::Result<Self, D::Error> {
+                    const VARIANTS: &[&str] = &[#(#names),*];
+                    struct Visitor;
+                    impl<'de> _serde::de::Visitor<'de> for Visitor {
+                        type Value = #ty;
+                        fn expecting(
+                            &self,
+                            f: &mut ::core::fmt::Formatter<'_>,
+                        ) -> ::core::fmt::Result {
+                            f.write_str(::core::concat!("a variant of ", #ty_name))
+                        }
+                        fn visit_str<E: _serde::de::Error>(
+                            self,
+                            v: &str,
+                        ) -> ::core::result::Result<#ty, E> {
+                            v.parse().map_err(|_| E::unknown_variant(v, VARIANTS))
+                        }
+                        fn visit_bytes<E: _serde::de::Error>(
+                            self,
+                            v: &[u8],
+                        ) -> ::core::result::Result<#ty, E> {
+                            match ::core::str::from_utf8(v) {
+                                ::core::result::Result::Ok(s) => self.visit_str(s),
+                                ::core::result::Result::Err(_) => ::core::result::Result::Err(
+                                    E::invalid_value(_serde::de::Unexpected::Bytes(v), &self),
+                                ),
+                            }
+                        }
+                    }
+                    d.deserialize_str(Visitor)
+                }
+            }
+        };
+    }
+}
+
+#[derive(Clone, Copy)]
+enum RenameRule {
+    Lower,
+    Upper,
+    Pascal,
+    Camel,
+    Snake,
+    ScreamingSnake,
+    Kebab,
+    ScreamingKebab,
+}
+
+impl RenameRule {
+    fn parse(lit: &LitStr) -> syn::Result<Self> {
+        Ok(match lit.value().as_str() {
+            "lowercase" => RenameRule::Lower,
+            "UPPERCASE" => RenameRule::Upper,
+            "PascalCase" => RenameRule::Pascal,
+            "camelCase" => RenameRule::Camel,
+            "snake_case" => RenameRule::Snake,
+            "SCREAMING_SNAKE_CASE" => RenameRule::ScreamingSnake,
+            "kebab-case" => RenameRule::Kebab,
+            "SCREAMING-KEBAB-CASE" => RenameRule::ScreamingKebab,
+            _ => return Err(Error::new(lit.span(), "unknown `rename_all` rule")),
+        })
+    }
+
+    // Variant names are assumed to be PascalCase, the same as serde does.
+    fn apply(self, variant: &str) -> String {
+        match self {
+            RenameRule::Pascal => variant.to_string(),
+            RenameRule::Lower => variant.to_ascii_lowercase(),
+            RenameRule::Upper => variant.to_ascii_uppercase(),
+            RenameRule::Camel => {
+                let mut chars = variant.chars();
+                match chars.next() {
+                    Some(c) => c.to_ascii_lowercase().to_string() + chars.as_str(),
+                    None => String::new(),
+                }
+            }
+            RenameRule::Snake => {
+                let mut out = String::new();
+                for (i, c) in variant.char_indices() {
+                    if i > 0 && c.is_uppercase() {
+                        out.push('_');
+                    }
+                    out.push(c.to_ascii_lowercase());
+                }
+                out
+            }
+            RenameRule::ScreamingSnake => RenameRule::Snake.apply(variant).to_ascii_uppercase(),
+            RenameRule::Kebab => RenameRule::Snake.apply(variant).replace('_', "-"),
+            RenameRule::ScreamingKebab => {
+                RenameRule::ScreamingSnake.apply(variant).replace('_', "-")
+            }
+        }
+    }
+}
@@ -0,0 +1,102 @@
+use arcstr::{ArcStr, ArcStrEnum, ParseVariantError};
+use std::convert::TryFrom;
+
+#[derive(ArcStrEnum, Debug, PartialEq, Clone, Copy)]
+#[arcstr(rename_all = "UPPERCASE", serde)]
+enum Method {
+    Get,
+    Post,
+    #[arcstr(rename = "delete")]
+    Delete,
+}
+
+#[derive(ArcStrEnum, Debug, PartialEq, Clone, Copy)]
+enum Plain {
+    First,
+    r#Second,
+}
+
+#[derive(ArcStrEnum)]
+enum Empty {}
+
+macro_rules! rename_all_test {
+    ($name:ident, $rule:literal, $expected:expr) => {
+        #[derive(ArcStrEnum, Debug, PartialEq, Clone, Copy)]
+        #[arcstr(rename_all = $rule)]
+        enum $name {
+            HttpRequest,
+            A,
+            UserId2,
+        }
+        let all = [$name::HttpRequest, $name::A, $name::UserId2];
+        let names: Vec<ArcStr> = all.iter().map(|v| v.as_arcstr()).collect();
+        assert_eq!(names, $expected, "{}", $rule);
+        for (v, n) in all.iter().zip(&names) {
+            assert_eq!(n.parse::<$name>().unwrap(), *v);
+        }
+    };
+}
+
+#[test]
+fn test_as_arcstr() {
+    const POST: ArcStr = Method::Post.as_arcstr();
+    assert_eq!(POST, "POST");
+    assert_eq!(Method::Delete.as_arcstr(), "delete");
+    for m in &[Method::Get, Method::Post, Method::Delete] {
+        let s = m.as_arcstr();
+        assert!(ArcStr::is_static(&s));
+        assert_eq!(ArcStr::from(*m), s);
+    }
+    assert_eq!(Plain::First.as_arcstr(), "First");
+    assert_eq!(Plain::Second.as_arcstr(), "Second");
+    let _ = |e: Empty| e.as_arcstr();
+}
+
+#[test]
+fn test_parse() {
+    assert_eq!("GET".parse::<Method>(), Ok(Method::Get));
+    assert_eq!(Method::try_from("delete"), Ok(Method::Delete));
+    assert_eq!("Second".parse::<Plain>(), Ok(Plain::Second));
+
+    let err: ParseVariantError = "Delete".parse::<Method>().unwrap_err();
+    assert_eq!(err.enum_name(), "Method");
+    assert_eq!(err.input(), "Delete");
+    assert_eq!(err.to_string(), r#"unknown variant of `Method`: "Delete""#);
+    assert!(Method::try_from("get").is_err());
+    assert!("".parse::<Empty>().is_err());
+}
+
+#[test]
+fn test_rename_all() {
+    rename_all_test!(Lower, "lowercase", ["httprequest", "a", "userid2"]);
+    rename_all_test!(Upper, "UPPERCASE", ["HTTPREQUEST", "A", "USERID2"]);
+    rename_all_test!(Pascal, "PascalCase", ["HttpRequest", "A", "UserId2"]);
+    rename_all_test!(Camel, "camelCase", ["httpRequest", "a", "userId2"]);
+    rename_all_test!(Snake, "snake_case", ["http_request", "a", "user_id2"]);
+    rename_all_test!(
+        ScreamingSnake,
+        "SCREAMING_SNAKE_CASE",
+        ["HTTP_REQUEST", "A", "USER_ID2"]
+    );
+    rename_all_test!(Kebab, "kebab-case", ["http-request", "a", "user-id2"]);
+    rename_all_test!(
+        ScreamingKebab,
+        "SCREAMING-KEBAB-CASE",
+        ["HTTP-REQUEST", "A", "USER-ID2"]
+    );
+}
+
+#[test]
+fn test_serde() {
+    let all = vec![Method::Get, Method::Delete, Method::Post];
+    let json = serde_json::to_string(&all).unwrap();
+    assert_eq!(json, r#"["GET","delete","POST"]"#);
+    assert_eq!(serde_json::from_str::<Vec<Method>>(&json).unwrap(), all);
+
+    let err = serde_json::from_str::<Method>(r#""PUT""#).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "unknown variant `PUT`, expected one of `GET`, `POST`, `delete` at line 1 column 5"
+    );
+    assert!(serde_json::from_str::<Method>("1").is_err());
+}
@@ -0,0 +1,112 @@
+//! Benchmarks for code which spends most of its time dereferencing `ArcStr`s.
+//!
+//! These don't use a benchmarking framework (to avoid the dependency), so the
+//! numbers are fairly rough. Each measurement is the median of several runs.
+//! Run with `cargo bench --bench deref`.
+//!
+//! For reference, here's the median of three runs on one x86_64 core, before
+//! and after `ArcStr` switched from pointing at its header to pointing at its
+//! data (in ns, lower is better):
+//!
+//! | benchmark                   | header pointer | data pointer |
+//! |-----------------------------|----------------|--------------|
+//! | `Arc<str>` deref            | 1.15           | 1.04         |
+//! | `ArcStr` deref              | 2.96           | 2.92         |
+//! | `ArcStr` (from_owner) deref | 4.78           | 4.73         |
+//! | `ArcStr == &str`            | 3.57           | 3.62         |
+//! | `ArcStr` (from_owner) `==`  | 5.20           | 5.12         |
+//!
+//! The differences are within the noise. Both versions check whether the
+//! string is external on every deref, and that branch is predictable for
+//! normal strings. Most of the remaining gap to `Arc<str>` comes from loading
+//! the length from the header, since `Arc<str>` keeps it in the pointer.
+use arcstr::ArcStr;
+use std::sync::Arc;
+use std::time::Instant;
+
+const STRINGS: usize = 200_000;
+const ROUNDS: usize = 20;
+const RUNS: usize = 15;
+
+// A list of strings in a shuffled order, so that walking it touches memory in
+// a different order than it was allocated in (as in most real programs).
+fn words() -> Vec<String> {
+    let mut words: Vec<String> = (0..STRINGS)
+        .map(|i| format!("word-{}-{}", i, "x".repeat(i % 24)))
+        .collect();
+    // xorshift, to avoid depending on `rand`.
+    let mut state = 0x2545_f491_4f6c_dd1du64;
+    for i in (1..words.len()).rev() {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        words.swap(i, (state % (i as u64 + 1)) as usize);
+    }
+    words
+}
+
+// `std::hint::black_box` is newer than our MSRV.
+fn black_box<T>(x: T) -> T {
+    unsafe {
+        let ret = std::ptr::read_volatile(&x);
+        std::mem::forget(x);
+        ret
+    }
+}
+
+fn median_ns(mut f: impl FnMut()) -> f64 {
+    f();
+    let mut times: Vec<f64> = (0..RUNS)
+        .map(|_| {
+            let start = Instant::now();
+            f();
+            start.elapsed().as_nanos() as f64 / (ROUNDS * STRINGS) as f64
+        })
+        .collect();
+    times.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    times[RUNS / 2]
+}
+
+fn bench_len<T: AsRef<str>>(name: &str, items: &[T]) {
+    let ns = median_ns(|| {
+        for _ in 0..ROUNDS {
+            for s in black_box(items) {
+                let s = s.as_ref();
+                black_box(s.as_bytes().last());
+            }
+        }
+    });
+    println!("{:<30} {:>7.3} ns/deref", name, ns);
+}
+
+fn bench_eq<T: AsRef<str>>(name: &str, items: &[T], needle: &str) {
+    let ns = median_ns(|| {
+        let mut hits = 0;
+        for _ in 0..ROUNDS {
+            for s in black_box(items) {
+                hits += (s.as_ref() == black_box(needle)) as usize;
+            }
+        }
+        black_box(hits);
+    });
+    println!("{:<30} {:>7.3} ns/compare", name, ns);
+}
+
+fn main() {
+    let words = words();
+    let arcstrs: Vec<ArcStr> = words.iter().map(|s| ArcStr::from(&s[..])).collect();
+    let owners: Vec<ArcStr> = words.iter().cloned().map(ArcStr::from_owner).collect();
+    let arcs: Vec<Arc<str>> = words.iter().map(|s| Arc::from(&s[..])).collect();
+    let strs: Vec<&str> = words.iter().map(|s| &s[..]).collect();
+
+    bench_len("&str", &strs);
+    bench_len("Arc<str>", &arcs);
+    bench_len("ArcStr", &arcstrs);
+    bench_len("ArcStr (from_owner)", &owners);
+
+    let needle = "word-5000-xxxxxxxx";
+    bench_eq("&str == &str", &strs, needle);
+    bench_eq("Arc<str> == &str", &arcs, needle);
+    bench_eq("ArcStr == &str", &arcstrs, needle);
+    bench_eq("ArcStr (from_owner) == &str", &owners, needle);
+}
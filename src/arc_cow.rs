@@ -0,0 +1,486 @@
+use crate::ArcStr;
+use alloc::borrow::Cow;
+use alloc::boxed::Box;
+use alloc::string::String;
+use core::marker::PhantomData;
+use core::mem::ManuallyDrop;
+use core::ptr::NonNull;
+
+/// Either a borrowed `&'a str` or a shared [`ArcStr`], in two words.
+///
+/// This is intended for APIs like parsers, which want to borrow from their
+/// input when possible, but otherwise hand out a shared string. `Cow<'a, str>`
+/// can do this too, but it's three words, and its owned side is a `String`,
+/// which needs to be copied to be shared.
+///
+/// `ArcCow` is the same size as a `&str` (and `Option<ArcCow>` is as well).
+/// This works by storing whether or not we hold an `ArcStr` in the top bit of
+/// the length, which is always unused, since a `str` can't be longer than
+/// `isize::MAX` bytes.
+///
+/// Two words is as small as this can get without copying. An `ArcStr` fits
+/// in one because its length lives in its heap allocation, but a borrowed
+/// `&str` has nowhere to keep its length except next to its pointer.
+///
+/// It derefs to `str`, and implements the same traits as `ArcStr` does. Use
+/// [`ArcCow::into_owned`] to get an `ArcStr` out of it, which only allocates
+/// if it was borrowed.
+///
+/// # Examples
+///
+/// ```
+/// # use arcstr::{ArcCow, ArcStr};
+/// fn unquote(s: &str) -> ArcCow<'_> {
+///     let s = s.trim_matches('"');
+///     if s.contains('\\') {
+///         ArcCow::from(ArcStr::from(s.replace('\\', "")))
+///     } else {
+///         ArcCow::from(s)
+///     }
+/// }
+///
+/// let a = unquote(r#""abc""#);
+/// assert!(a.is_borrowed());
+/// assert_eq!(a, "abc");
+///
+/// let b = unquote(r#""a\bc""#);
+/// assert!(b.is_owned());
+/// assert_eq!(b, "abc");
+///
+/// let owned: ArcStr = a.into_owned();
+/// assert_eq!(owned, "abc");
+/// ```
+pub struct ArcCow<'a> {
+    // Either the data of a `&'a str`, or the result of `ArcStr::into_raw`.
+    ptr: NonNull<u8>,
+    // The length of the string, with `OWNED` set if `ptr` came from an
+    // `ArcStr`.
+    len: usize,
+    _marker: PhantomData<&'a str>,
+}
+
+const OWNED: usize = !(usize::MAX >> 1);
+
+// Safety: we're either a `&str` or an `ArcStr`, both of which are `Send` and
+// `Sync`.
+unsafe impl Send for ArcCow<'_> {}
+unsafe impl Sync for ArcCow<'_> {}
+
+impl<'a> ArcCow<'a> {
+    /// Construct a new empty string. This doesn't allocate.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use arcstr::ArcCow;
+    /// let s = ArcCow::new();
+    /// assert_eq!(s, "");
+    /// ```
+    #[inline]
+    pub const fn new() -> Self {
+        Self::borrowed("")
+    }
+
+    /// Create an `ArcCow` which borrows `s`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use arcstr::ArcCow;
+    /// const HELLO: ArcCow<'static> = ArcCow::borrowed("hello");
+    /// assert!(HELLO.is_borrowed());
+    /// ```
+    #[inline]
+    pub const fn borrowed(s: &'a str) -> Self {
+        Self {
+            // Safety: references are never null.
+            ptr: unsafe { NonNull::new_unchecked(s.as_ptr() as *mut u8) },
+            len: s.len(),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Create an `ArcCow` which holds `s`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use arcstr::{ArcCow, ArcStr};
+    /// let s = ArcCow::owned(ArcStr::from("hello"));
+    /// assert!(s.is_owned());
+    /// ```
+    #[inline]
+    pub fn owned(s: ArcStr) -> Self {
+        let len = s.len();
+        Self {
+            ptr: ArcStr::into_raw(s).cast(),
+            len: len | OWNED,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns true if this holds an `ArcStr`.
+    #[inline]
+    pub fn is_owned(&self) -> bool {
+        self.len & OWNED != 0
+    }
+
+    /// Returns true if this borrows a `&'a str`.
+    #[inline]
+    pub fn is_borrowed(&self) -> bool {
+        !self.is_owned()
+    }
+
+    /// Returns the length of the string, in bytes.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len & !OWNED
+    }
+
+    /// Returns true if the string is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Extract a string slice containing our data.
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        match self.as_arcstr() {
+            Some(s) => s.as_str(),
+            // Safety: we're borrowed, so `ptr` and `len` came from a `&'a str`.
+            None => unsafe {
+                core::str::from_utf8_unchecked(core::slice::from_raw_parts(
+                    self.ptr.as_ptr(),
+                    self.len,
+                ))
+            },
+        }
+    }
+
+    /// Returns the borrowed `&'a str`, or `None` if this holds an `ArcStr`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use arcstr::{ArcCow, ArcStr};
+    /// let input = String::from("abc");
+    /// let s = ArcCow::from(&input[..]);
+    /// let borrowed: &str = s.as_borrowed().unwrap();
+    /// drop(s);
+    /// assert_eq!(borrowed, "abc");
+    ///
+    /// assert!(ArcCow::from(ArcStr::from("abc")).as_borrowed().is_none());
+    /// ```
+    #[inline]
+    pub fn as_borrowed(&self) -> Option<&'a str> {
+        if self.is_owned() {
+            None
+        } else {
+            // Safety: we're borrowed, so `ptr` and `len` came from a `&'a str`.
+            Some(unsafe {
+                core::str::from_utf8_unchecked(core::slice::from_raw_parts(
+                    self.ptr.as_ptr(),
+                    self.len,
+                ))
+            })
+        }
+    }
+
+    /// Returns the `ArcStr` we hold, or `None` if this is borrowed.
+    #[inline]
+    pub fn as_arcstr(&self) -> Option<&ArcStr> {
+        if self.is_owned() {
+            // Safety: `ArcStr` is a `repr(transparent)` wrapper around a
+            // `NonNull`, and `ptr` came from `ArcStr::into_raw`.
+            Some(unsafe { &*(&self.ptr as *const NonNull<u8> as *const ArcStr) })
+        } else {
+            None
+        }
+    }
+
+    /// Convert this into an `ArcStr`, copying the string if it's borrowed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use arcstr::{ArcCow, ArcStr};
+    /// let a = ArcStr::from("abc");
+    /// let b = ArcCow::from(a.clone()).into_owned();
+    /// assert!(ArcStr::ptr_eq(&a, &b));
+    ///
+    /// let c = ArcCow::from("abc").into_owned();
+    /// assert_eq!(c, "abc");
+    /// ```
+    #[inline]
+    pub fn into_owned(self) -> ArcStr {
+        let this = ManuallyDrop::new(self);
+        if this.is_owned() {
+            // Safety: `ptr` came from `ArcStr::into_raw`, and we won't drop
+            // `this`.
+            unsafe { ArcStr::from_raw(this.ptr.cast()) }
+        } else {
+            ArcStr::from(this.as_str())
+        }
+    }
+
+    /// Convert this into an `ArcCow<'static>`, copying the string into an
+    /// `ArcStr` if it's borrowed.
+    #[inline]
+    pub fn into_static(self) -> ArcCow<'static> {
+        ArcCow::owned(self.into_owned())
+    }
+}
+
+impl Clone for ArcCow<'_> {
+    #[inline]
+    fn clone(&self) -> Self {
+        match self.as_arcstr() {
+            Some(s) => Self::owned(s.clone()),
+            None => Self {
+                ptr: self.ptr,
+                len: self.len,
+                _marker: PhantomData,
+            },
+        }
+    }
+}
+
+impl Drop for ArcCow<'_> {
+    #[inline]
+    fn drop(&mut self) {
+        if self.is_owned() {
+            // Safety: `ptr` came from `ArcStr::into_raw`, and this is the only
+            // place we give up ownership of it (aside from `into_owned`, which
+            // doesn't run this).
+            drop(unsafe { ArcStr::from_raw(self.ptr.cast()) });
+        }
+    }
+}
+
+impl core::ops::Deref for ArcCow<'_> {
+    type Target = str;
+    #[inline]
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl Default for ArcCow<'_> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> From<&'a str> for ArcCow<'a> {
+    #[inline]
+    fn from(s: &'a str) -> Self {
+        Self::borrowed(s)
+    }
+}
+
+impl<'a> From<&'a String> for ArcCow<'a> {
+    #[inline]
+    fn from(s: &'a String) -> Self {
+        Self::borrowed(s)
+    }
+}
+
+impl<'a> From<&'a ArcStr> for ArcCow<'a> {
+    /// Borrows the string, rather than cloning the `ArcStr`.
+    #[inline]
+    fn from(s: &'a ArcStr) -> Self {
+        Self::borrowed(s)
+    }
+}
+
+impl From<ArcStr> for ArcCow<'_> {
+    #[inline]
+    fn from(s: ArcStr) -> Self {
+        Self::owned(s)
+    }
+}
+
+impl From<String> for ArcCow<'_> {
+    #[inline]
+    fn from(s: String) -> Self {
+        Self::owned(ArcStr::from(s))
+    }
+}
+
+impl From<Box<str>> for ArcCow<'_> {
+    #[inline]
+    fn from(s: Box<str>) -> Self {
+        Self::owned(ArcStr::from(s))
+    }
+}
+
+impl<'a> From<Cow<'a, str>> for ArcCow<'a> {
+    #[inline]
+    fn from(s: Cow<'a, str>) -> Self {
+        match s {
+            Cow::Borrowed(s) => Self::borrowed(s),
+            Cow::Owned(s) => Self::from(s),
+        }
+    }
+}
+
+impl<'a> From<ArcCow<'a>> for ArcStr {
+    #[inline]
+    fn from(s: ArcCow<'a>) -> Self {
+        s.into_owned()
+    }
+}
+
+impl<'a> From<ArcCow<'a>> for Cow<'a, str> {
+    #[inline]
+    fn from(s: ArcCow<'a>) -> Self {
+        match s.as_borrowed() {
+            Some(b) => Cow::Borrowed(b),
+            // This borrows static `ArcStr`s rather than copying them.
+            None => Cow::from(s.into_owned()),
+        }
+    }
+}
+
+impl<'a> From<ArcCow<'a>> for String {
+    #[inline]
+    fn from(s: ArcCow<'a>) -> Self {
+        String::from(s.as_str())
+    }
+}
+
+impl<'a, 'b> core::iter::FromIterator<&'b str> for ArcCow<'a> {
+    #[inline]
+    fn from_iter<T: IntoIterator<Item = &'b str>>(iter: T) -> Self {
+        Self::owned(ArcStr::from_iter(iter))
+    }
+}
+
+impl core::iter::FromIterator<char> for ArcCow<'_> {
+    #[inline]
+    fn from_iter<T: IntoIterator<Item = char>>(iter: T) -> Self {
+        Self::owned(ArcStr::from_iter(iter))
+    }
+}
+
+impl core::fmt::Debug for ArcCow<'_> {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        core::fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl core::fmt::Display for ArcCow<'_> {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        core::fmt::Display::fmt(self.as_str(), f)
+    }
+}
+
+impl PartialEq for ArcCow<'_> {
+    #[inline]
+    fn eq(&self, o: &Self) -> bool {
+        self.len() == o.len() && self.as_str() == o.as_str()
+    }
+}
+
+impl Eq for ArcCow<'_> {}
+
+macro_rules! impl_peq {
+    (@one $a:ty, $b:ty) => {
+        impl<'a, 'b> PartialEq<$b> for $a {
+            #[inline]
+            fn eq(&self, s: &$b) -> bool {
+                PartialEq::eq(&self[..], &s[..])
+            }
+        }
+    };
+    ($(($a:ty, $b:ty),)+) => {$(
+        impl_peq!(@one $a, $b);
+        impl_peq!(@one $b, $a);
+    )+};
+}
+
+impl_peq! {
+    (ArcCow<'a>, str),
+    (ArcCow<'a>, &'b str),
+    (ArcCow<'a>, String),
+    (ArcCow<'a>, Cow<'b, str>),
+    (ArcCow<'a>, Box<str>),
+    (ArcCow<'a>, ArcStr),
+    (ArcCow<'a>, alloc::sync::Arc<str>),
+    (ArcCow<'a>, alloc::rc::Rc<str>),
+}
+
+impl PartialOrd for ArcCow<'_> {
+    #[inline]
+    fn partial_cmp(&self, s: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(s))
+    }
+}
+
+impl Ord for ArcCow<'_> {
+    #[inline]
+    fn cmp(&self, s: &Self) -> core::cmp::Ordering {
+        self.as_str().cmp(s.as_str())
+    }
+}
+
+impl core::hash::Hash for ArcCow<'_> {
+    #[inline]
+    fn hash<H: core::hash::Hasher>(&self, h: &mut H) {
+        self.as_str().hash(h)
+    }
+}
+
+macro_rules! impl_index {
+    ($($IdxT:ty,)*) => {$(
+        impl core::ops::Index<$IdxT> for ArcCow<'_> {
+            type Output = str;
+            #[inline]
+            fn index(&self, i: $IdxT) -> &Self::Output {
+                &self.as_str()[i]
+            }
+        }
+    )*};
+}
+
+impl_index! {
+    core::ops::RangeFull,
+    core::ops::Range<usize>,
+    core::ops::RangeFrom<usize>,
+    core::ops::RangeTo<usize>,
+    core::ops::RangeInclusive<usize>,
+    core::ops::RangeToInclusive<usize>,
+}
+
+impl AsRef<str> for ArcCow<'_> {
+    #[inline]
+    fn as_ref(&self) -> &str {
+        self
+    }
+}
+
+impl AsRef<[u8]> for ArcCow<'_> {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+impl core::borrow::Borrow<str> for ArcCow<'_> {
+    #[inline]
+    fn borrow(&self) -> &str {
+        self
+    }
+}
+
+impl core::str::FromStr for ArcCow<'_> {
+    type Err = core::convert::Infallible;
+    #[inline]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self::owned(ArcStr::from(s)))
+    }
+}
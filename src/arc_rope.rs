@@ -0,0 +1,720 @@
+use crate::ArcStr;
+use alloc::collections::VecDeque;
+use alloc::string::String;
+use alloc::sync::Arc;
+use core::iter::FromIterator;
+use core::ops::{Bound, RangeBounds};
+use core::sync::atomic::{AtomicUsize, Ordering::Relaxed};
+
+/// An immutable string made of shared [`ArcStr`] pieces, which can be
+/// concatenated and sliced without copying any string data.
+///
+/// Concatenating two `ArcStr`s always has to copy both of them into a new
+/// allocation, which gets expensive when building up large strings from
+/// large pieces (say, in a templating engine). An `ArcRope` instead keeps its
+/// pieces as the leaves of a balanced tree, and only allocates a few small
+/// tree nodes when concatenating or slicing. Both take `O(log n)` time in the
+/// number of pieces, as does indexing by byte or `char` (although see
+/// [`char_count`](Self::char_count) for a caveat about the latter).
+///
+/// Once you're done building it, [`flatten`](Self::flatten) copies a rope into
+/// a single `ArcStr`, with exactly one allocation. Alternatively, it
+/// implements `Display`, and [`chunks`](Self::chunks) iterates over the pieces
+/// directly.
+///
+/// A rope made from a single `ArcStr` (including a `const` one from
+/// [`literal_arcstr!`](crate::literal_arcstr) or
+/// [`literal_concat!`](crate::literal_concat)) doesn't allocate at all.
+///
+/// # Examples
+///
+/// ```
+/// # use arcstr::{ArcRope, ArcStr};
+/// const OPEN: ArcStr = arcstr::literal_concat!("<p>");
+/// const CLOSE: ArcStr = arcstr::literal_concat!("</p>");
+///
+/// let body = ArcRope::from(ArcStr::from("Hello, world!"));
+/// let html = ArcRope::from(OPEN).concat(&body).concat(&CLOSE.into());
+/// assert_eq!(html, "<p>Hello, world!</p>");
+/// assert_eq!(html.len(), 20);
+/// assert_eq!(html.slice(3..8), "Hello");
+/// assert_eq!(html.char_at(3), Some('H'));
+/// assert_eq!(html.chunks().collect::<Vec<_>>(), ["<p>", "Hello, world!", "</p>"]);
+///
+/// let flat: ArcStr = html.flatten();
+/// assert_eq!(flat, "<p>Hello, world!</p>");
+/// ```
+#[derive(Clone)]
+pub struct ArcRope(Repr);
+
+#[derive(Clone)]
+enum Repr {
+    Leaf(Leaf),
+    Branch(Arc<Branch>),
+}
+
+// Stored in place of a char count that hasn't been computed yet. Counting the
+// chars of a non-ASCII piece takes linear time, so slicing leaves this for the
+// pieces it cuts (and the branches above them), to be filled in the first time
+// the count is needed.
+const UNKNOWN: usize = usize::MAX;
+
+// A piece of a rope: `s[start..end]`, which has `chars` chars (or `UNKNOWN`).
+struct Leaf {
+    s: ArcStr,
+    start: usize,
+    end: usize,
+    chars: AtomicUsize,
+}
+
+struct Branch {
+    left: Repr,
+    right: Repr,
+    len: usize,
+    // The sum of the char counts of `left` and `right`, or `UNKNOWN`.
+    chars: AtomicUsize,
+    // The height of this node. Leaves have a height of 0, and the heights of
+    // the children of each node differ by at most 1, which keeps the tree
+    // balanced (as in an AVL tree).
+    height: u32,
+}
+
+impl Leaf {
+    const fn empty() -> Self {
+        Self {
+            s: ArcStr::new(),
+            start: 0,
+            end: 0,
+            chars: AtomicUsize::new(0),
+        }
+    }
+
+    fn new(s: ArcStr) -> Self {
+        // Free for ASCII strings, since the header caches whether we are.
+        let chars = AtomicUsize::new(s.char_count());
+        let end = s.len();
+        Self {
+            s,
+            start: 0,
+            end,
+            chars,
+        }
+    }
+
+    fn chars(&self) -> usize {
+        let n = self.chars.load(Relaxed);
+        if n != UNKNOWN {
+            return n;
+        }
+        let n = self.as_str().chars().count();
+        self.chars.store(n, Relaxed);
+        n
+    }
+
+    #[inline]
+    fn as_str(&self) -> &str {
+        // Safety: `start..end` is always in bounds, and on char boundaries.
+        unsafe { self.s.get_unchecked(self.start..self.end) }
+    }
+
+    // Only ASCII pieces can have as many chars as bytes. A count that's
+    // `UNKNOWN` never matches, which just means callers take the slow path.
+    #[inline]
+    fn is_ascii(&self) -> bool {
+        self.chars.load(Relaxed) == self.end - self.start
+    }
+
+    // Returns `self[a..b]`. `a` and `b` must be char boundaries.
+    fn slice(&self, a: usize, b: usize) -> Self {
+        if a == b {
+            return Self::empty();
+        }
+        Self {
+            s: self.s.clone(),
+            start: self.start + a,
+            end: self.start + b,
+            chars: AtomicUsize::new(if self.is_ascii() { b - a } else { UNKNOWN }),
+        }
+    }
+}
+
+impl Clone for Leaf {
+    fn clone(&self) -> Self {
+        Self {
+            s: self.s.clone(),
+            start: self.start,
+            end: self.end,
+            chars: AtomicUsize::new(self.chars.load(Relaxed)),
+        }
+    }
+}
+
+impl Repr {
+    #[inline]
+    fn len(&self) -> usize {
+        match self {
+            Repr::Leaf(l) => l.end - l.start,
+            Repr::Branch(b) => b.len,
+        }
+    }
+
+    // Computes the char count first if it's `UNKNOWN`, and remembers it.
+    fn chars(&self) -> usize {
+        match self {
+            Repr::Leaf(l) => l.chars(),
+            Repr::Branch(b) => {
+                let n = b.chars.load(Relaxed);
+                if n != UNKNOWN {
+                    return n;
+                }
+                let n = b.left.chars() + b.right.chars();
+                b.chars.store(n, Relaxed);
+                n
+            }
+        }
+    }
+
+    // The char count if it's already known, or `UNKNOWN`.
+    #[inline]
+    fn known_chars(&self) -> usize {
+        match self {
+            Repr::Leaf(l) => l.chars.load(Relaxed),
+            Repr::Branch(b) => b.chars.load(Relaxed),
+        }
+    }
+
+    #[inline]
+    fn height(&self) -> u32 {
+        match self {
+            Repr::Leaf(_) => 0,
+            Repr::Branch(b) => b.height,
+        }
+    }
+
+    fn branch(left: Repr, right: Repr) -> Repr {
+        debug_assert!(left.height().abs_diff(right.height()) <= 1);
+        let (lc, rc) = (left.known_chars(), right.known_chars());
+        let chars = if lc == UNKNOWN || rc == UNKNOWN {
+            UNKNOWN
+        } else {
+            lc + rc
+        };
+        Repr::Branch(Arc::new(Branch {
+            len: left.len() + right.len(),
+            chars: AtomicUsize::new(chars),
+            height: left.height().max(right.height()) + 1,
+            left,
+            right,
+        }))
+    }
+
+    // Concatenate `a` and `b`, rebalancing as needed. This is the usual AVL
+    // join: we walk down the side of the taller tree until we find a subtree
+    // about as tall as the shorter one, and rotate our way back up. It takes
+    // time proportional to the difference in their heights.
+    fn join(a: Repr, b: Repr) -> Repr {
+        if a.len() == 0 {
+            return b;
+        }
+        if b.len() == 0 {
+            return a;
+        }
+        let (ha, hb) = (a.height(), b.height());
+        if ha > hb + 1 {
+            let (l, r) = a.children();
+            Self::balance(l, Self::join(r, b))
+        } else if hb > ha + 1 {
+            let (l, r) = b.children();
+            Self::balance(Self::join(a, l), r)
+        } else {
+            Self::branch(a, b)
+        }
+    }
+
+    // Make a node out of `l` and `r`, whose heights differ by at most 2.
+    fn balance(l: Repr, r: Repr) -> Repr {
+        let (hl, hr) = (l.height(), r.height());
+        if hl > hr + 1 {
+            let (ll, lr) = l.children();
+            if ll.height() >= lr.height() {
+                Self::branch(ll, Self::branch(lr, r))
+            } else {
+                let (lrl, lrr) = lr.children();
+                Self::branch(Self::branch(ll, lrl), Self::branch(lrr, r))
+            }
+        } else if hr > hl + 1 {
+            let (rl, rr) = r.children();
+            if rr.height() >= rl.height() {
+                Self::branch(Self::branch(l, rl), rr)
+            } else {
+                let (rll, rlr) = rl.children();
+                Self::branch(Self::branch(l, rll), Self::branch(rlr, rr))
+            }
+        } else {
+            Self::branch(l, r)
+        }
+    }
+
+    // Only called on branches, which is guaranteed by their height.
+    #[inline]
+    fn children(self) -> (Repr, Repr) {
+        match self {
+            Repr::Branch(b) => (b.left.clone(), b.right.clone()),
+            Repr::Leaf(_) => unreachable!("leaves have no children"),
+        }
+    }
+
+    // Returns `self[a..b]`. `a` and `b` must be char boundaries.
+    fn slice(&self, a: usize, b: usize) -> Repr {
+        if a == 0 && b == self.len() {
+            return self.clone();
+        }
+        match self {
+            Repr::Leaf(l) => Repr::Leaf(l.slice(a, b)),
+            Repr::Branch(br) => {
+                let mid = br.left.len();
+                if b <= mid {
+                    br.left.slice(a, b)
+                } else if a >= mid {
+                    br.right.slice(a - mid, b - mid)
+                } else {
+                    Self::join(br.left.slice(a, mid), br.right.slice(0, b - mid))
+                }
+            }
+        }
+    }
+
+    // Find the leaf containing byte `i`, and the offset of `i` in it.
+    fn leaf_at_byte(&self, mut i: usize) -> (&Leaf, usize) {
+        let mut node = self;
+        loop {
+            match node {
+                Repr::Leaf(l) => return (l, i),
+                Repr::Branch(b) => {
+                    let mid = b.left.len();
+                    node = if i < mid {
+                        &b.left
+                    } else {
+                        i -= mid;
+                        &b.right
+                    };
+                }
+            }
+        }
+    }
+
+    // Find the leaf containing char `n`, and the index of `n` in it.
+    fn leaf_at_char(&self, mut n: usize) -> (&Leaf, usize) {
+        let mut node = self;
+        loop {
+            match node {
+                Repr::Leaf(l) => return (l, n),
+                Repr::Branch(b) => {
+                    let mid = b.left.chars();
+                    node = if n < mid {
+                        &b.left
+                    } else {
+                        n -= mid;
+                        &b.right
+                    };
+                }
+            }
+        }
+    }
+}
+
+impl ArcRope {
+    /// Create an empty rope. This doesn't allocate.
+    #[inline]
+    pub const fn new() -> Self {
+        Self(Repr::Leaf(Leaf::empty()))
+    }
+
+    /// Returns the length of this rope in bytes.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns true if this rope is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the number of `char`s in this rope.
+    ///
+    /// This takes constant time, except for the first call after a
+    /// [`slice`](Self::slice) cuts through a non-ASCII piece: to keep slicing
+    /// fast, that piece's chars aren't counted until they're needed, which
+    /// takes time linear in its length. [`char_at`](Self::char_at) may need
+    /// to count them too. Either way, the count is remembered afterwards.
+    #[inline]
+    pub fn char_count(&self) -> usize {
+        self.0.chars()
+    }
+
+    /// Returns a new rope containing `self` followed by `other`, without
+    /// copying the data of either.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use arcstr::ArcRope;
+    /// let a = ArcRope::from("foo");
+    /// let b = a.concat(&ArcRope::from("bar"));
+    /// assert_eq!(a, "foo");
+    /// assert_eq!(b, "foobar");
+    /// ```
+    pub fn concat(&self, other: &ArcRope) -> ArcRope {
+        Self(Repr::join(self.0.clone(), other.0.clone()))
+    }
+
+    /// Returns the part of this rope in `range` (which is in bytes), without
+    /// copying any string data.
+    ///
+    /// # Panics
+    ///
+    /// Like slicing a `str`, this panics if either end of the range is out of
+    /// bounds, or isn't on a char boundary.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use arcstr::ArcRope;
+    /// let r = ArcRope::from("Hello, ").concat(&ArcRope::from("wörld"));
+    /// assert_eq!(r.slice(5..8), ", w");
+    /// assert_eq!(r.slice(7..), "wörld");
+    /// ```
+    pub fn slice(&self, range: impl RangeBounds<usize>) -> ArcRope {
+        let start = match range.start_bound() {
+            Bound::Included(&n) => n,
+            Bound::Excluded(&n) => n.checked_add(1).expect("range start overflowed"),
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&n) => n.checked_add(1).expect("range end overflowed"),
+            Bound::Excluded(&n) => n,
+            Bound::Unbounded => self.len(),
+        };
+        assert!(
+            start <= end && end <= self.len(),
+            "byte range {}..{} is out of bounds of a rope of length {}",
+            start,
+            end,
+            self.len(),
+        );
+        assert!(
+            self.is_char_boundary(start) && self.is_char_boundary(end),
+            "byte range {}..{} is not on char boundaries",
+            start,
+            end,
+        );
+        Self(self.0.slice(start, end))
+    }
+
+    /// Returns true if byte `index` is the start of a `char` (or the end of
+    /// the rope), like [`str::is_char_boundary`].
+    pub fn is_char_boundary(&self, index: usize) -> bool {
+        if index == 0 || index == self.len() {
+            return true;
+        }
+        match self.byte_at(index) {
+            // Not a UTF-8 continuation byte.
+            Some(b) => (b as i8) >= -0x40,
+            None => false,
+        }
+    }
+
+    /// Returns the byte at `index`, or `None` if it's out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use arcstr::ArcRope;
+    /// let r = ArcRope::from("ab").concat(&ArcRope::from("cd"));
+    /// assert_eq!(r.byte_at(2), Some(b'c'));
+    /// assert_eq!(r.byte_at(4), None);
+    /// ```
+    pub fn byte_at(&self, index: usize) -> Option<u8> {
+        if index >= self.len() {
+            return None;
+        }
+        let (leaf, i) = self.0.leaf_at_byte(index);
+        Some(leaf.as_str().as_bytes()[i])
+    }
+
+    /// Returns the `char` at index `n` (counting in `char`s, not bytes), or
+    /// `None` if it's out of bounds.
+    ///
+    /// Finding the piece containing the `char` takes `O(log n)` time. Within
+    /// that piece, this takes constant time if it's ASCII, and is linear in
+    /// its length otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use arcstr::ArcRope;
+    /// let r = ArcRope::from("àb").concat(&ArcRope::from("çd"));
+    /// assert_eq!(r.char_at(2), Some('ç'));
+    /// assert_eq!(r.char_at(4), None);
+    /// ```
+    pub fn char_at(&self, n: usize) -> Option<char> {
+        if n >= self.char_count() {
+            return None;
+        }
+        let (leaf, i) = self.0.leaf_at_char(n);
+        if leaf.is_ascii() {
+            Some(leaf.as_str().as_bytes()[i] as char)
+        } else {
+            leaf.as_str().chars().nth(i)
+        }
+    }
+
+    /// Returns an iterator over the pieces of this rope, in order, as `&str`s.
+    /// Empty pieces are skipped.
+    pub fn chunks(&self) -> ArcRopeChunks<'_> {
+        ArcRopeChunks {
+            stack: core::iter::once(&self.0).collect(),
+        }
+    }
+
+    /// Returns an iterator over the `char`s of this rope.
+    pub fn chars(&self) -> impl DoubleEndedIterator<Item = char> + '_ {
+        self.chunks().flat_map(str::chars)
+    }
+
+    /// Returns an iterator over the bytes of this rope.
+    pub fn bytes(&self) -> impl DoubleEndedIterator<Item = u8> + '_ {
+        self.chunks().flat_map(str::bytes)
+    }
+
+    /// Copy the contents of this rope into a single `ArcStr`.
+    ///
+    /// This performs at most one allocation, and none at all if the rope
+    /// consists of a single, entire `ArcStr`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use arcstr::{ArcRope, ArcStr};
+    /// let s = ArcStr::from("abc");
+    /// let r = ArcRope::from(s.clone());
+    /// assert!(ArcStr::ptr_eq(&r.flatten(), &s));
+    /// assert_eq!(r.concat(&r).flatten(), "abcabc");
+    /// ```
+    pub fn flatten(&self) -> ArcStr {
+        if let Repr::Leaf(l) = &self.0 {
+            if l.start == 0 && l.end == l.s.len() {
+                return l.s.clone();
+            }
+        }
+        ArcStr::build(self.len(), |w| {
+            for c in self.chunks() {
+                w.push_str(c);
+            }
+        })
+    }
+
+    // Compare to `o` a chunk at a time.
+    fn eq_str(&self, o: &str) -> bool {
+        if self.len() != o.len() {
+            return false;
+        }
+        let mut rest = o.as_bytes();
+        for c in self.chunks() {
+            let (head, tail) = rest.split_at(c.len());
+            if head != c.as_bytes() {
+                return false;
+            }
+            rest = tail;
+        }
+        true
+    }
+}
+
+/// An iterator over the pieces of an [`ArcRope`], returned by
+/// [`ArcRope::chunks`].
+#[derive(Clone)]
+pub struct ArcRopeChunks<'a> {
+    // The subtrees we've yet to visit, in order. `next` descends into the
+    // front one, and `next_back` into the back one.
+    stack: VecDeque<&'a Repr>,
+}
+
+impl<'a> Iterator for ArcRopeChunks<'a> {
+    type Item = &'a str;
+    fn next(&mut self) -> Option<&'a str> {
+        while let Some(node) = self.stack.pop_front() {
+            match node {
+                Repr::Leaf(l) if l.start != l.end => return Some(l.as_str()),
+                Repr::Leaf(_) => {}
+                Repr::Branch(b) => {
+                    self.stack.push_front(&b.right);
+                    self.stack.push_front(&b.left);
+                }
+            }
+        }
+        None
+    }
+}
+
+impl DoubleEndedIterator for ArcRopeChunks<'_> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        while let Some(node) = self.stack.pop_back() {
+            match node {
+                Repr::Leaf(l) if l.start != l.end => return Some(l.as_str()),
+                Repr::Leaf(_) => {}
+                Repr::Branch(b) => {
+                    self.stack.push_back(&b.left);
+                    self.stack.push_back(&b.right);
+                }
+            }
+        }
+        None
+    }
+}
+
+impl core::iter::FusedIterator for ArcRopeChunks<'_> {}
+
+impl core::fmt::Debug for ArcRopeChunks<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+impl Default for ArcRope {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<ArcStr> for ArcRope {
+    #[inline]
+    fn from(s: ArcStr) -> Self {
+        Self(Repr::Leaf(Leaf::new(s)))
+    }
+}
+
+impl From<&ArcStr> for ArcRope {
+    #[inline]
+    fn from(s: &ArcStr) -> Self {
+        Self::from(s.clone())
+    }
+}
+
+impl From<&str> for ArcRope {
+    #[inline]
+    fn from(s: &str) -> Self {
+        Self::from(ArcStr::from(s))
+    }
+}
+
+impl From<String> for ArcRope {
+    #[inline]
+    fn from(s: String) -> Self {
+        Self::from(ArcStr::from(s))
+    }
+}
+
+impl From<ArcRope> for ArcStr {
+    #[inline]
+    fn from(r: ArcRope) -> Self {
+        r.flatten()
+    }
+}
+
+impl From<&ArcRope> for String {
+    fn from(r: &ArcRope) -> Self {
+        let mut s = String::with_capacity(r.len());
+        s.extend(r.chunks());
+        s
+    }
+}
+
+impl FromIterator<ArcStr> for ArcRope {
+    fn from_iter<I: IntoIterator<Item = ArcStr>>(iter: I) -> Self {
+        iter.into_iter()
+            .fold(Self::new(), |r, s| Self(Repr::join(r.0, Self::from(s).0)))
+    }
+}
+
+impl FromIterator<ArcRope> for ArcRope {
+    fn from_iter<I: IntoIterator<Item = ArcRope>>(iter: I) -> Self {
+        iter.into_iter()
+            .fold(Self::new(), |r, s| Self(Repr::join(r.0, s.0)))
+    }
+}
+
+impl core::fmt::Display for ArcRope {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        if f.width().is_some() || f.precision().is_some() {
+            // Padding and truncation need to see the whole string.
+            return core::fmt::Display::fmt(&String::from(self), f);
+        }
+        self.chunks().try_for_each(|c| f.write_str(c))
+    }
+}
+
+impl core::fmt::Debug for ArcRope {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        core::fmt::Debug::fmt(&String::from(self), f)
+    }
+}
+
+impl PartialEq for ArcRope {
+    fn eq(&self, o: &Self) -> bool {
+        self.len() == o.len() && self.bytes().eq(o.bytes())
+    }
+}
+
+impl Eq for ArcRope {}
+
+impl PartialOrd for ArcRope {
+    #[inline]
+    fn partial_cmp(&self, o: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(o))
+    }
+}
+
+impl Ord for ArcRope {
+    fn cmp(&self, o: &Self) -> core::cmp::Ordering {
+        self.bytes().cmp(o.bytes())
+    }
+}
+
+impl core::hash::Hash for ArcRope {
+    fn hash<H: core::hash::Hasher>(&self, h: &mut H) {
+        // Hash the same way regardless of how the rope is split into pieces,
+        // and whether its char counts have been computed yet.
+        for b in self.bytes() {
+            h.write_u8(b);
+        }
+        h.write_u8(0xff);
+    }
+}
+
+macro_rules! impl_rope_peq {
+    ($($other:ty),+) => {$(
+        impl<'a> PartialEq<$other> for ArcRope {
+            #[inline]
+            fn eq(&self, o: &$other) -> bool {
+                self.eq_str(&o[..])
+            }
+        }
+        impl<'a> PartialEq<ArcRope> for $other {
+            #[inline]
+            fn eq(&self, o: &ArcRope) -> bool {
+                o.eq_str(&self[..])
+            }
+        }
+    )+};
+}
+
+impl_rope_peq! {
+    str,
+    &'a str,
+    String,
+    ArcStr
+}
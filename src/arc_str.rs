@@ -1,5 +1,5 @@
 use core::alloc::Layout;
-use core::mem::{align_of, size_of};
+use core::mem::{align_of, size_of, ManuallyDrop};
 use core::ptr::NonNull;
 #[cfg(not(all(loom, test)))]
 pub(crate) use core::sync::atomic::{AtomicUsize, Ordering};
@@ -9,6 +9,13 @@ pub(crate) use loom::sync::atomic::{AtomicUsize, Ordering};
 use alloc::borrow::Cow;
 use alloc::boxed::Box;
 use alloc::string::String;
+use alloc::vec::Vec;
+
+#[cfg(feature = "pool")]
+use crate::pool::{alloc as alloc_block, dealloc as dealloc_block};
+use crate::{ArcCow, ArcStrRef, StaticSet, UniqueArcStr};
+#[cfg(not(feature = "pool"))]
+use alloc::alloc::{alloc as alloc_block, dealloc as dealloc_block};
 
 /// A better atomically-reference counted string type.
 ///
@@ -95,7 +102,7 @@ use alloc::string::String;
 /// assert_eq!(WOW, "cool robot!");
 /// ```
 #[repr(transparent)]
-pub struct ArcStr(NonNull<ThinInner>);
+pub struct ArcStr(NonNull<u8>);
 
 unsafe impl Sync for ArcStr {}
 unsafe impl Send for ArcStr {}
@@ -144,7 +151,7 @@ impl ArcStr {
     /// ```
     #[inline]
     pub fn len(&self) -> usize {
-        unsafe { ThinInner::get_len_flags(self.0.as_ptr()).len() }
+        unsafe { ThinInner::get_len_flags(self.inner()).len() }
     }
 
     /// Returns true if this `ArcStr` is empty.
@@ -161,6 +168,70 @@ impl ArcStr {
         self.len() == 0
     }
 
+    /// Returns true if this `ArcStr` only contains ASCII characters.
+    ///
+    /// This gives the same result as [`str::is_ascii`], but doesn't need to
+    /// look at the data, since it's recorded when the string is created.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use arcstr::ArcStr;
+    /// assert!(ArcStr::from("abc").is_ascii());
+    /// assert!(!ArcStr::from("héllo").is_ascii());
+    /// assert!(ArcStr::new().is_ascii());
+    /// ```
+    #[inline]
+    pub fn is_ascii(&self) -> bool {
+        unsafe { ThinInner::get_len_flags(self.inner()).is_ascii() }
+    }
+
+    /// Returns the number of `char`s in this `ArcStr`, which is the same as
+    /// `self.chars().count()`.
+    ///
+    /// This takes constant time for ASCII strings (see [`ArcStr::is_ascii`]),
+    /// and is linear in the length of the string otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use arcstr::ArcStr;
+    /// assert_eq!(ArcStr::from("abc").char_count(), 3);
+    /// assert_eq!(ArcStr::from("héllo").char_count(), 5);
+    /// ```
+    #[inline]
+    pub fn char_count(&self) -> usize {
+        if self.is_ascii() {
+            self.len()
+        } else {
+            self.chars().count()
+        }
+    }
+
+    /// Returns the `char` at index `n` (counting in `char`s, not bytes), or
+    /// `None` if there are `n` or fewer `char`s. This is the same as
+    /// `self.chars().nth(n)`.
+    ///
+    /// This takes constant time for ASCII strings (see [`ArcStr::is_ascii`]),
+    /// and is linear in `n` otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use arcstr::ArcStr;
+    /// assert_eq!(ArcStr::from("abc").nth_char(1), Some('b'));
+    /// assert_eq!(ArcStr::from("héllo").nth_char(1), Some('é'));
+    /// assert_eq!(ArcStr::from("abc").nth_char(3), None);
+    /// ```
+    #[inline]
+    pub fn nth_char(&self, n: usize) -> Option<char> {
+        if self.is_ascii() {
+            self.as_bytes().get(n).map(|&b| b as char)
+        } else {
+            self.chars().nth(n)
+        }
+    }
+
     /// Convert us to a `std::string::String`.
     ///
     /// This is provided as an inherent method to avoid needing to route through
@@ -174,6 +245,7 @@ impl ArcStr {
     /// assert_eq!(s.to_string(), "abc");
     /// ```
     #[inline]
+    #[allow(clippy::inherent_to_string_shadow_display)]
     pub fn to_string(&self) -> String {
         #[cfg(not(feature = "std"))]
         use alloc::borrow::ToOwned;
@@ -182,6 +254,11 @@ impl ArcStr {
 
     /// Extract a byte slice containing the string's data.
     ///
+    /// The `ArcStr` points directly at its bytes (with its header just before
+    /// them), so this only needs to read the length from the header. Strings
+    /// created by [`ArcStr::from_owner`] are the exception, and also read the
+    /// data pointer from the header.
+    ///
     /// # Examples
     ///
     /// ```
@@ -191,12 +268,19 @@ impl ArcStr {
     /// ```
     #[inline]
     pub fn as_bytes(&self) -> &[u8] {
-        let p = self.0.as_ptr();
+        let p = self.inner();
         unsafe {
-            let len = ThinInner::get_len_flags(p).len();
-            let data = (p as *const u8).add(memoffset::offset_of!(ThinInner, data));
-            debug_assert_eq!(&(*p).data as *const [u8; 0] as usize, data as usize);
-            core::slice::from_raw_parts(data, len)
+            let lf = ThinInner::get_len_flags(p);
+            let data = if lf.is_external() {
+                (*(p as *const ExternalInner<()>)).data
+            } else {
+                debug_assert_eq!(
+                    &(*p).data as *const [u8; 0] as usize,
+                    self.0.as_ptr() as usize
+                );
+                self.0.as_ptr() as *const u8
+            };
+            core::slice::from_raw_parts(data, lf.len())
         }
     }
 
@@ -206,6 +290,9 @@ impl ArcStr {
     /// signature, we also guarantee the pointer has an alignment of at least 8
     /// bytes, even on platforms where a lower alignment would be acceptable.
     ///
+    /// The pointer should be treated as opaque. If you need a pointer to the
+    /// string's bytes (for example, for FFI), use [`ArcStr::into_raw_data`].
+    ///
     /// # Examples
     ///
     /// ```
@@ -247,6 +334,72 @@ impl ArcStr {
         Self(ptr.cast())
     }
 
+    /// Returns a pointer to the string's bytes.
+    ///
+    /// This is the same as `self.as_bytes().as_ptr()`. For strings which
+    /// weren't created by [`ArcStr::from_owner`], it's also the same as the
+    /// pointer the `ArcStr` holds internally, so it's free to compute.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use arcstr::ArcStr;
+    /// let s = ArcStr::from("abc");
+    /// assert_eq!(s.as_ptr(), s.as_bytes().as_ptr());
+    /// ```
+    #[inline]
+    pub fn as_ptr(&self) -> *const u8 {
+        self.as_bytes().as_ptr()
+    }
+
+    /// Like [`ArcStr::into_raw`], but the result is guaranteed to point at the
+    /// string's bytes, so it can be handed to code (such as C code) which
+    /// reads it directly, along with the length. The bytes are not
+    /// nul-terminated.
+    ///
+    /// Strings created by [`ArcStr::from_owner`] don't store their data
+    /// inline, so they're copied into a new `ArcStr` first. This is the only
+    /// case where this allocates.
+    ///
+    /// The pointer is at least 8-byte aligned, and must be passed to
+    /// [`ArcStr::from_raw_data`] to free it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use arcstr::ArcStr;
+    /// let s = ArcStr::from("abcd");
+    /// let len = s.len();
+    /// let p = ArcStr::into_raw_data(s);
+    /// let bytes = unsafe { std::slice::from_raw_parts(p.as_ptr(), len) };
+    /// assert_eq!(bytes, b"abcd");
+    /// let s = unsafe { ArcStr::from_raw_data(p) };
+    /// assert_eq!(s, "abcd");
+    /// ```
+    #[inline]
+    pub fn into_raw_data(this: Self) -> NonNull<u8> {
+        let this = if unsafe { ThinInner::get_len_flags(this.inner()).is_external() } {
+            Self::from(this.as_str())
+        } else {
+            this
+        };
+        let p = this.0;
+        core::mem::forget(this);
+        p
+    }
+
+    /// The opposite of [`ArcStr::into_raw_data`].
+    ///
+    /// # Safety
+    ///
+    /// This function must be used on a valid pointer returned from
+    /// [`ArcStr::into_raw_data`]. Additionally, you must ensure that a given
+    /// `ArcStr` instance is only dropped once.
+    #[inline]
+    pub unsafe fn from_raw_data(ptr: NonNull<u8>) -> Self {
+        Self(ptr)
+    }
+
     /// Returns true if the two `ArcStr`s point to the same allocation.
     ///
     /// Note that functions like `PartialEq` check this already, so there's
@@ -290,6 +443,10 @@ impl ArcStr {
     /// return value, `ArcStr::strong_count(&s).unwrap_or(usize::MAX)` is
     /// frequently reasonable.
     ///
+    /// Strings created by an [`ArcStrArena`](crate::ArcStrArena) share a count
+    /// with the rest of their block rather than having one of their own, so
+    /// this returns `None` for them too.
+    ///
     /// # Safety
     ///
     /// This method by itself is safe, but using it correctly requires extra
@@ -297,8 +454,8 @@ impl ArcStr {
     /// potentially between calling this method and acting on the result.
     ///
     /// However, it may never change from `None` to `Some` or from `Some` to
-    /// `None` for a given `ArcStr` — whether or not it is static is determined
-    /// at construction, and never changes.
+    /// `None` for a given `ArcStr` — whether it is static (or from an arena) is
+    /// determined at construction, and never changes.
     ///
     /// # Examples
     ///
@@ -323,8 +480,9 @@ impl ArcStr {
     /// ```
     #[inline]
     pub fn strong_count(this: &Self) -> Option<usize> {
-        let this = this.0.as_ptr();
-        if unsafe { ThinInner::get_len_flags(this).is_static() } {
+        let this = this.inner();
+        let lf = unsafe { ThinInner::get_len_flags(this) };
+        if lf.is_static() || lf.is_arena() {
             None
         } else {
             unsafe { Some((*this).strong.load(Ordering::SeqCst)) }
@@ -355,7 +513,7 @@ impl ArcStr {
     /// ```
     #[inline]
     pub fn is_static(this: &Self) -> bool {
-        unsafe { ThinInner::get_len_flags(this.0.as_ptr()).is_static() }
+        unsafe { ThinInner::get_len_flags(this.inner()).is_static() }
     }
 
     /// Returns true if `this` is a "static" ArcStr. For example, if it was
@@ -385,7 +543,7 @@ impl ArcStr {
     /// ```
     #[inline]
     pub fn as_static(this: &Self) -> Option<&'static str> {
-        if unsafe { ThinInner::get_len_flags(this.0.as_ptr()).is_static() } {
+        if unsafe { ThinInner::get_len_flags(this.inner()).is_static() } {
             // We know static strings live forever, so they can have a static lifetime.
             Some(unsafe { &*(this.as_str() as *const str) })
         } else {
@@ -393,18 +551,761 @@ impl ArcStr {
         }
     }
 
+    /// Concatenate `pieces` into a new `ArcStr`.
+    ///
+    /// This computes the length of the result up front, so (unlike going
+    /// through `[&str]::concat` and converting the resulting `String`) the data
+    /// is only allocated and copied once. If only one of the pieces is
+    /// non-empty, and it's an `ArcStr`, this returns a clone of it instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use arcstr::ArcStr;
+    /// let s = ArcStr::concat(&["foo", "bar", "baz"]);
+    /// assert_eq!(s, "foobarbaz");
+    /// assert_eq!(ArcStr::concat::<&str>(&[]), "");
+    ///
+    /// let foo = ArcStr::from("foo");
+    /// let s = ArcStr::concat(&[ArcStr::new(), foo.clone()]);
+    /// assert!(ArcStr::ptr_eq(&s, &foo));
+    /// ```
+    pub fn concat<S: StrPiece>(pieces: &[S]) -> Self {
+        if let Some(s) = only_arcstr(pieces) {
+            return s.clone();
+        }
+        let len = total_len(pieces.iter().map(|p| p.as_ref().len()));
+        Self::build(len, |w| {
+            for p in pieces {
+                w.push_str(p.as_ref());
+            }
+        })
+    }
+
+    /// Join the items of `pieces` into a new `ArcStr`, placing `sep` between
+    /// each of them.
+    ///
+    /// Like [`ArcStr::concat`], the result is allocated exactly once, and if
+    /// `pieces` has a single item which is an `ArcStr`, that's returned
+    /// without allocating. To find out how much to allocate, the items
+    /// themselves (but not their contents) are first collected into a `Vec`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use arcstr::ArcStr;
+    /// let s = ArcStr::join(&["foo", "bar", "baz"], ", ");
+    /// assert_eq!(s, "foo, bar, baz");
+    ///
+    /// let words = "the quick brown fox".split(' ').rev();
+    /// assert_eq!(ArcStr::join(words, "_"), "fox_brown_quick_the");
+    /// ```
+    pub fn join<I>(pieces: I, sep: &str) -> Self
+    where
+        I: IntoIterator,
+        I::Item: StrPiece,
+    {
+        let pieces = pieces.into_iter().collect::<Vec<_>>();
+        if let [p] = &pieces[..] {
+            if let Some(s) = p.as_arcstr() {
+                return s.clone();
+            }
+        }
+        let seps = sep.len().checked_mul(pieces.len().saturating_sub(1));
+        let len = total_len(
+            pieces
+                .iter()
+                .map(|p| p.as_ref().len())
+                .chain(Some(seps.unwrap_or_else(|| alloc_overflow()))),
+        );
+        Self::build(len, |w| {
+            for (i, p) in pieces.iter().enumerate() {
+                if i != 0 {
+                    w.push_str(sep);
+                }
+                w.push_str(p.as_ref());
+            }
+        })
+    }
+
+    /// Create a new `ArcStr` containing `n` copies of `self`.
+    ///
+    /// Note: This shadows `str::repeat`, which returns a `String`. The result
+    /// is allocated exactly once, and if `n` is 1 this returns a clone of
+    /// `self` without allocating at all.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of the result would overflow.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use arcstr::ArcStr;
+    /// let s = ArcStr::from("abc");
+    /// assert_eq!(s.repeat(3), "abcabcabc");
+    /// assert_eq!(s.repeat(0), "");
+    /// assert!(ArcStr::ptr_eq(&s, &s.repeat(1)));
+    /// ```
+    pub fn repeat(&self, n: usize) -> Self {
+        if n == 1 {
+            return self.clone();
+        }
+        let len = self
+            .len()
+            .checked_mul(n)
+            .unwrap_or_else(|| alloc_overflow());
+        Self::build(len, |w| {
+            for _ in 0..n {
+                w.push_str(self);
+            }
+        })
+    }
+
+    /// Appends `s` to the end of this string, returning the result.
+    ///
+    /// If `self` is the only reference to a (non-static) heap-allocated string,
+    /// this grows the existing allocation in place with `realloc`, which
+    /// avoids copying the existing data in many cases. Otherwise, it allocates
+    /// a new string of the correct size and copies both parts into it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of the result would overflow.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use arcstr::ArcStr;
+    /// let s = ArcStr::from("foo");
+    /// let s = s.append("bar");
+    /// assert_eq!(s, "foobar");
+    ///
+    /// // Other references to the string are unaffected.
+    /// let shared = s.clone();
+    /// assert_eq!(s.append("baz"), "foobarbaz");
+    /// assert_eq!(shared, "foobar");
+    /// ```
+    pub fn append(self, s: &str) -> Self {
+        if s.is_empty() {
+            return self;
+        }
+        let len = self.len();
+        match self.grow_unique(s.len()) {
+            Ok(p) => unsafe {
+                let data = ThinInner::data_ptr(p.as_ptr());
+                core::ptr::copy_nonoverlapping(s.as_ptr(), data.add(len), s.len());
+                if !s.is_ascii() {
+                    ThinInner::set_ascii(p.as_ptr(), false);
+                }
+                Self::from_inner(p)
+            },
+            Err(this) => Self::build(total_len([len, s.len()].iter().copied()), |w| {
+                w.push_str(&this);
+                w.push_str(s);
+            }),
+        }
+    }
+
+    /// Prepends `s` to the start of this string, returning the result.
+    ///
+    /// Like [`ArcStr::append`], this grows the allocation in place if `self` is
+    /// the only reference to a (non-static) heap-allocated string. Note that
+    /// the existing data still must be moved over to make room for `s`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of the result would overflow.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use arcstr::ArcStr;
+    /// let s = ArcStr::from("bar");
+    /// let s = s.prepend("foo");
+    /// assert_eq!(s, "foobar");
+    /// ```
+    pub fn prepend(self, s: &str) -> Self {
+        if s.is_empty() {
+            return self;
+        }
+        let len = self.len();
+        match self.grow_unique(s.len()) {
+            Ok(p) => unsafe {
+                let data = ThinInner::data_ptr(p.as_ptr());
+                core::ptr::copy(data, data.add(s.len()), len);
+                core::ptr::copy_nonoverlapping(s.as_ptr(), data, s.len());
+                if !s.is_ascii() {
+                    ThinInner::set_ascii(p.as_ptr(), false);
+                }
+                Self::from_inner(p)
+            },
+            Err(this) => Self::build(total_len([s.len(), this.len()].iter().copied()), |w| {
+                w.push_str(s);
+                w.push_str(&this);
+            }),
+        }
+    }
+
+    /// Attempt to convert this string into a [`UniqueArcStr`], which allows
+    /// mutating the string data in place.
+    ///
+    /// This succeeds if `self` is the only reference to a heap-allocated
+    /// string (that is, if [`ArcStr::strong_count`] would return `Some(1)`),
+    /// and otherwise returns `self` back in the `Err` variant. Strings created
+    /// by [`ArcStr::from_owner`] or an [`ArcStrArena`](crate::ArcStrArena)
+    /// don't own their memory alone, so this always fails for them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use arcstr::ArcStr;
+    /// let s = ArcStr::from("abc");
+    /// let mut unique = s.try_unique().unwrap();
+    /// unique.make_ascii_uppercase();
+    /// let s: ArcStr = unique.into();
+    /// assert_eq!(s, "ABC");
+    ///
+    /// let shared = s.clone();
+    /// assert!(s.try_unique().is_err());
+    /// # drop(shared);
+    /// ```
+    #[inline]
+    pub fn try_unique(self) -> Result<UniqueArcStr, Self> {
+        if self.is_unique() {
+            Ok(unsafe { UniqueArcStr::from_arcstr_unchecked(self) })
+        } else {
+            Err(self)
+        }
+    }
+
+    /// Borrow this as an [`ArcStrRef`], a `Copy` handle which can be upgraded
+    /// back into an `ArcStr` when needed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use arcstr::{ArcStr, ArcStrRef};
+    /// fn maybe_keep(s: ArcStrRef<'_>, keep: &mut Vec<ArcStr>) {
+    ///     if s.starts_with('k') {
+    ///         keep.push(s.upgrade());
+    ///     }
+    /// }
+    ///
+    /// let mut kept = vec![];
+    /// let s = ArcStr::from("keep me");
+    /// maybe_keep(s.borrow_ref(), &mut kept);
+    /// assert!(ArcStr::ptr_eq(&s, &kept[0]));
+    /// ```
+    #[inline]
+    pub fn borrow_ref(&self) -> ArcStrRef<'_> {
+        // Safety: the pointer came from a live `ArcStr`, which is borrowed for
+        // the lifetime of the result.
+        unsafe { ArcStrRef::from_raw(self.0.cast()) }
+    }
+
+    // Returns a pointer to our data that's valid for writes, assuming we're a
+    // unique non-static string.
+    #[inline]
+    pub(crate) unsafe fn data_ptr_mut(&self) -> *mut u8 {
+        self.0.as_ptr()
+    }
+
+    // Recompute the cached ASCII flag, after the data may have been changed
+    // through a `UniqueArcStr`. Safety: same as for `data_ptr_mut`.
+    #[inline]
+    pub(crate) unsafe fn refresh_ascii(&self) {
+        if !self.is_empty() {
+            ThinInner::set_ascii(self.inner(), self.as_bytes().is_ascii());
+        }
+    }
+
+    // Initializes a string in the `ArcStrArena` block memory at `dst`, which
+    // must have room for `ArcStr::arena_size(data.len())` bytes and be 8-byte
+    // aligned. The caller is responsible for holding a reference to the block
+    // on behalf of the new string.
+    pub(crate) unsafe fn init_in_arena(dst: NonNull<u8>, data: &str) -> Self {
+        debug_assert!(!data.is_empty());
+        debug_assert_eq!(dst.as_ptr() as usize % align_of::<ThinInner>(), 0);
+        let lf = LenFlags::from_len_arena(data.len())
+            .unwrap_or_else(|| alloc_overflow())
+            .with_ascii(data.is_ascii());
+        let ptr = dst.as_ptr() as *mut ThinInner;
+        // Arena strings are counted by their block, so `strong` is unused, but
+        // we still give it a defined value.
+        core::ptr::write(&mut (*ptr).len_flags, lf);
+        core::ptr::write(&mut (*ptr).strong, AtomicUsize::new(0));
+        core::ptr::copy_nonoverlapping(data.as_ptr(), ThinInner::data_ptr(ptr), data.len());
+        Self::from_inner(NonNull::new_unchecked(ptr))
+    }
+
+    // The number of bytes `init_in_arena` needs for a string of `len` bytes,
+    // rounded up so that the next string is aligned.
+    #[inline]
+    pub(crate) const fn arena_size(len: usize) -> usize {
+        const ALIGN: usize = align_of::<ThinInner>();
+        (DATA_OFFSET + len + ALIGN - 1) & !(ALIGN - 1)
+    }
+
+    // Returns true if `self` is a heap-allocated string with no other
+    // references, which stores its data inline in an allocation of its own
+    // (that is, it wasn't created by `from_owner` or an `ArcStrArena`).
+    #[inline]
+    pub(crate) fn is_unique(&self) -> bool {
+        let p = self.inner();
+        unsafe {
+            let lf = ThinInner::get_len_flags(p);
+            // The `Acquire` synchronizes with the `Release` decrement in
+            // `drop`, so that any reads other threads did before dropping their
+            // reference happen-before whatever we do with the string next.
+            !lf.is_static()
+                && !lf.is_external()
+                && !lf.is_arena()
+                && (*p).strong.load(Ordering::Acquire) == 1
+        }
+    }
+
+    // If `self` is unique, grow its allocation to hold `extra` more bytes, and
+    // return the new pointer. The new bytes are uninitialized, and must be
+    // written before the result is used as an `ArcStr`.
+    fn grow_unique(self, extra: usize) -> Result<NonNull<ThinInner>, Self> {
+        if !self.is_unique() {
+            return Err(self);
+        }
+        let lf = unsafe { ThinInner::get_len_flags(self.inner()) };
+        // Padded strings aren't allocated in the way `realloc` expects. (Arena
+        // strings never count as unique, so they don't get this far.)
+        if lf.is_padded() {
+            return Err(self);
+        }
+        let new_len = total_len([self.len(), extra].iter().copied());
+        let p = ManuallyDrop::new(self).inner();
+        Ok(unsafe { ThinInner::realloc(NonNull::new_unchecked(p), new_len) })
+    }
+
+    /// Create an `ArcStr` which shares the string data of `owner`, rather than
+    /// copying it.
+    ///
+    /// The `owner` is moved to the heap, and kept alive (and unmodified) until
+    /// the last reference to the resulting `ArcStr` is dropped, at which point
+    /// it is dropped too. This is useful for sharing large strings that already
+    /// live somewhere else, such as in a `String`, a `Box<str>`, or a wrapper
+    /// around a memory map or network buffer, without paying for a copy.
+    ///
+    /// Note that this still performs one (small) allocation to hold the owner
+    /// and our reference count. For short strings, copying the data with
+    /// `ArcStr::from` is likely to be just as fast, and it uses less memory.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use arcstr::ArcStr;
+    /// let big = "a very large string".repeat(1000);
+    /// let data_ptr = big.as_ptr();
+    /// let s = ArcStr::from_owner(big);
+    /// assert!(s.starts_with("a very large string"));
+    /// // No copy was made.
+    /// assert_eq!(s.as_ptr(), data_ptr);
+    /// ```
+    pub fn from_owner<O>(owner: O) -> Self
+    where
+        O: AsRef<str> + Send + Sync + 'static,
+    {
+        let p = Box::into_raw(Box::new(ExternalInner {
+            len_flags: LenFlags::EMPTY_STATIC,
+            strong: AtomicUsize::new(1),
+            data: core::ptr::null(),
+            drop_fn: ExternalInner::<O>::destroy,
+            owner,
+        }));
+        unsafe {
+            // Note: we only call `as_ref` after moving the owner to its final
+            // location, so that the pointer stays valid even for owners that
+            // store their data inline.
+            let s: &str = (*p).owner.as_ref();
+            if s.is_empty() {
+                drop(Box::from_raw(p));
+                return Self::new();
+            }
+            let lf = LenFlags::from_len_external(s.len())
+                .unwrap_or_else(|| alloc_overflow())
+                .with_ascii(s.is_ascii());
+            (*p).data = s.as_ptr();
+            (*p).len_flags = lf;
+            Self::from_inner(NonNull::new_unchecked(p as *mut ThinInner))
+        }
+    }
+
+    /// Create an `ArcStr` from `s`, returning the matching static entry of
+    /// `known` without allocating if there is one.
+    ///
+    /// This is useful for strings which are usually (but not always) one of a
+    /// few well-known values. See [`StaticSet`] and
+    /// [`static_set!`](crate::static_set) for more info.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use arcstr::ArcStr;
+    /// arcstr::static_set! {
+    ///     static LEVELS = ["error", "warn", "info"];
+    /// }
+    /// let a = ArcStr::from_known("warn", &LEVELS);
+    /// assert!(ArcStr::is_static(&a));
+    ///
+    /// let b = ArcStr::from_known("trace", &LEVELS);
+    /// assert_eq!(b, "trace");
+    /// assert!(!ArcStr::is_static(&b));
+    /// ```
+    #[inline]
+    pub fn from_known<const N: usize>(s: &str, known: &StaticSet<N>) -> Self {
+        match known.get(s) {
+            Some(k) => k.clone(),
+            None => Self::from(s),
+        }
+    }
+
+    /// The alignment of the data of strings created by
+    /// [`ArcStr::from_str_padded`].
+    pub const PADDED_ALIGN: usize = PADDED_ALIGN;
+
+    /// Create an `ArcStr` with a copy of `s`, whose data is aligned to
+    /// [`ArcStr::PADDED_ALIGN`] (32) bytes, and followed by at least `PAD`
+    /// zeroed bytes.
+    ///
+    /// This is intended for SIMD code (tokenizers, JSON parsers, etc), which
+    /// wants to use aligned loads, and to be able to read past the end of the
+    /// string without checking. The padding isn't part of the string, and
+    /// isn't included in its length.
+    ///
+    /// Use [`ArcStr::padding`] to check if an `ArcStr` was created this way.
+    /// Clones share the guarantee, as does mutating it in place through
+    /// [`ArcStr::try_unique`], but other operations which produce a new string
+    /// (like [`ArcStr::append`]) return an ordinary `ArcStr`.
+    ///
+    /// Unlike other constructors, this always allocates, even for an empty
+    /// string.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use arcstr::ArcStr;
+    /// let s = ArcStr::from_str_padded::<64>("{\"key\": [1, 2, 3]}");
+    /// assert_eq!(s.as_ptr() as usize % ArcStr::PADDED_ALIGN, 0);
+    /// assert_eq!(ArcStr::padding(&s), Some(64));
+    ///
+    /// // It's fine to read up to 64 bytes past the end of the string.
+    /// let tail = unsafe { std::slice::from_raw_parts(s.as_ptr().add(s.len()), 64) };
+    /// assert!(tail.iter().all(|&b| b == 0));
+    ///
+    /// assert_eq!(ArcStr::padding(&ArcStr::from("abc")), None);
+    /// ```
+    #[inline]
+    pub fn from_str_padded<const PAD: usize>(s: &str) -> Self {
+        Self::from_inner(ThinInner::allocate_padded(s, PAD))
+    }
+
+    /// If `this` was created by [`ArcStr::from_str_padded`], returns the
+    /// number of zeroed bytes that follow its data, and `None` otherwise.
+    ///
+    /// When this returns `Some`, the data is also aligned to
+    /// [`ArcStr::PADDED_ALIGN`] bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use arcstr::ArcStr;
+    /// let s = ArcStr::from_str_padded::<32>("abc");
+    /// assert_eq!(ArcStr::padding(&s), Some(32));
+    /// assert_eq!(ArcStr::padding(&s.clone()), Some(32));
+    /// assert_eq!(ArcStr::padding(&s.append("d")), None);
+    /// ```
+    #[inline]
+    pub fn padding(this: &Self) -> Option<usize> {
+        let p = this.inner();
+        unsafe {
+            if ThinInner::get_len_flags(p).is_padded() {
+                Some(ThinInner::padding(p))
+            } else {
+                None
+            }
+        }
+    }
+
+    // Allocate an `ArcStr` with room for exactly `len` bytes, and have `f` fill
+    // it in. Panics if `f` doesn't write exactly `len` bytes.
+    pub(crate) fn build(len: usize, f: impl FnOnce(&mut StrWriter)) -> Self {
+        if len == 0 {
+            return Self::new();
+        }
+        // Note: If `f` panics, dropping `this` frees the allocation without
+        // reading the (possibly uninitialized) data, so this is fine.
+        let this = Self::from_inner(ThinInner::allocate_uninit(len));
+        let mut w = StrWriter {
+            ptr: this.0.as_ptr(),
+            cap: len,
+            pos: 0,
+        };
+        f(&mut w);
+        assert_eq!(w.pos, len, "wrote the wrong number of bytes to an ArcStr");
+        unsafe { ThinInner::set_ascii(this.inner(), this.as_bytes().is_ascii()) };
+        this
+    }
+
+    // Collect `chars` into a single allocation. Since we can't know how many
+    // bytes we need up front, this grows it (doubling, like a `String`) as
+    // needed, and shrinks it to fit at the end, which allocators can usually do
+    // in place.
+    fn from_chars(mut chars: impl Iterator<Item = char>) -> Self {
+        let first = match chars.next() {
+            Some(c) => c,
+            None => return Self::new(),
+        };
+        let mut cap = chars.size_hint().0.saturating_add(1).max(8);
+        // Note: As in `build`, if `chars` panics, dropping `this` frees the
+        // allocation without reading the (possibly uninitialized) data.
+        let mut this = Self::from_inner(ThinInner::allocate_uninit(cap));
+        let mut pos = 0;
+        let mut ascii = true;
+        for c in core::iter::once(first).chain(chars) {
+            let mut buf = [0u8; 4];
+            let bytes = c.encode_utf8(&mut buf).as_bytes();
+            if bytes.len() > cap - pos {
+                cap = cap.checked_mul(2).unwrap_or_else(|| alloc_overflow());
+                let p = ManuallyDrop::new(this).inner();
+                this =
+                    Self::from_inner(unsafe { ThinInner::realloc(NonNull::new_unchecked(p), cap) });
+            }
+            unsafe {
+                core::ptr::copy_nonoverlapping(
+                    bytes.as_ptr(),
+                    this.data_ptr_mut().add(pos),
+                    bytes.len(),
+                );
+            }
+            pos += bytes.len();
+            ascii &= c.is_ascii();
+        }
+        if pos != cap {
+            let p = ManuallyDrop::new(this).inner();
+            this = Self::from_inner(unsafe { ThinInner::realloc(NonNull::new_unchecked(p), pos) });
+        }
+        unsafe { ThinInner::set_ascii(this.inner(), ascii) };
+        this
+    }
+
     // Not public API. Exists so the literal_arcstr macro can call it.
     #[inline]
     #[doc(hidden)]
     pub const unsafe fn new_static<B>(ptr: &'static StaticArcStrInner<B>) -> Self {
-        Self(NonNull::new_unchecked(ptr as *const _ as *mut ThinInner))
+        Self(NonNull::new_unchecked(
+            (ptr as *const StaticArcStrInner<B> as *mut u8).add(DATA_OFFSET),
+        ))
+    }
+
+    // Returns a pointer to our header, which lives `DATA_OFFSET` bytes before
+    // the data we point to. For static strings this is really a
+    // `ThinInnerStatic` (see the comment on `InnerRepr`), and for strings from
+    // `from_owner` it's an `ExternalInner`.
+    #[inline]
+    fn inner(&self) -> *mut ThinInner {
+        unsafe { self.0.as_ptr().sub(DATA_OFFSET) as *mut ThinInner }
+    }
+
+    #[inline]
+    fn from_inner(p: NonNull<ThinInner>) -> Self {
+        unsafe { Self(NonNull::new_unchecked(ThinInner::data_ptr(p.as_ptr()))) }
+    }
+
+    // Not public API. Exists so `literal_concat!` can read the data of a
+    // `const` ArcStr during const evaluation, where the only `ArcStr`s that can
+    // exist are static ones.
+    //
+    // Safety: `self` must be static.
+    #[inline]
+    pub(crate) const unsafe fn static_bytes(&self) -> &'static [u8] {
+        let data = self.0.as_ptr() as *const u8;
+        let len = (*(data.sub(DATA_OFFSET) as *const LenFlags)).len();
+        core::slice::from_raw_parts(data, len)
+    }
+}
+
+// Versions of `str`'s transformation methods which avoid allocating when the
+// result would be the same as the input.
+impl ArcStr {
+    /// Returns an ASCII-lowercased copy of this string, like
+    /// [`str::to_ascii_lowercase`].
+    ///
+    /// If there are no ASCII uppercase characters in `self`, this returns a
+    /// clone of `self` and does not allocate.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use arcstr::ArcStr;
+    /// let s = ArcStr::from("Hello, World!");
+    /// assert_eq!(s.to_ascii_lowercase_arc(), "hello, world!");
+    ///
+    /// let already_lower = ArcStr::from("hello");
+    /// assert!(ArcStr::ptr_eq(&already_lower, &already_lower.to_ascii_lowercase_arc()));
+    /// ```
+    pub fn to_ascii_lowercase_arc(&self) -> Self {
+        if !self.bytes().any(|b| b.is_ascii_uppercase()) {
+            return self.clone();
+        }
+        // ASCII case conversion never changes the length, or whether the
+        // string is ASCII, so we can just copy the data and lowercase it.
+        let out = Self::from(self.as_str());
+        unsafe {
+            core::slice::from_raw_parts_mut(out.data_ptr_mut(), out.len()).make_ascii_lowercase();
+        }
+        out
+    }
+
+    /// Returns a lowercased copy of this string, like [`str::to_lowercase`].
+    ///
+    /// If `self` is already entirely lowercase (that is, lowercasing it would
+    /// not change anything), this returns a clone of `self` and does not
+    /// allocate.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use arcstr::ArcStr;
+    /// let s = ArcStr::from("ὈΔΥΣΣΕΎΣ");
+    /// assert_eq!(s.to_lowercase_arc(), "ὀδυσσεύς");
+    ///
+    /// let already_lower = ArcStr::from("ὀδυσσεύς");
+    /// assert!(ArcStr::ptr_eq(&already_lower, &already_lower.to_lowercase_arc()));
+    /// ```
+    pub fn to_lowercase_arc(&self) -> Self {
+        if self.is_ascii() {
+            return self.to_ascii_lowercase_arc();
+        }
+        let unchanged = self.chars().all(|c| {
+            let mut lower = c.to_lowercase();
+            lower.next() == Some(c) && lower.next().is_none()
+        });
+        if unchanged {
+            return self.clone();
+        }
+        if self.contains('Σ') {
+            // Lowercasing a capital sigma depends on its surroundings, which
+            // is more than we're willing to reimplement here.
+            return Self::from(self.as_str().to_lowercase());
+        }
+        let len = total_len(
+            self.chars()
+                .flat_map(char::to_lowercase)
+                .map(char::len_utf8),
+        );
+        Self::build(len, |w| {
+            for c in self.chars().flat_map(char::to_lowercase) {
+                w.push_char(c);
+            }
+        })
+    }
+
+    /// Returns this string with leading and trailing whitespace removed, like
+    /// [`str::trim`].
+    ///
+    /// If there is no leading or trailing whitespace, this returns a clone of
+    /// `self` and does not allocate.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use arcstr::ArcStr;
+    /// let s = ArcStr::from("  abc\n");
+    /// assert_eq!(s.trim_arc(), "abc");
+    ///
+    /// let already_trimmed = ArcStr::from("abc");
+    /// assert!(ArcStr::ptr_eq(&already_trimmed, &already_trimmed.trim_arc()));
+    /// ```
+    pub fn trim_arc(&self) -> Self {
+        let trimmed = self.trim();
+        if trimmed.len() == self.len() {
+            self.clone()
+        } else {
+            Self::from(trimmed)
+        }
+    }
+
+    /// Replaces all matches of `from` with `to`, like [`str::replace`].
+    ///
+    /// If `from` does not occur in `self` (or if `from == to`), this returns a
+    /// clone of `self` and does not allocate. Otherwise, the result is
+    /// allocated exactly once.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use arcstr::ArcStr;
+    /// let s = ArcStr::from("this is old");
+    /// assert_eq!(s.replace_arc("old", "new"), "this is new");
+    /// assert!(ArcStr::ptr_eq(&s, &s.replace_arc("cool", "new")));
+    /// ```
+    pub fn replace_arc(&self, from: &str, to: &str) -> Self {
+        if from == to {
+            return self.clone();
+        }
+        let count = self.matches(from).count();
+        if count == 0 {
+            return self.clone();
+        }
+        let removed = from.len() * count;
+        let added = to.len().checked_mul(count);
+        let len = total_len(
+            Some(self.len() - removed)
+                .into_iter()
+                .chain(Some(added.unwrap_or_else(|| alloc_overflow()))),
+        );
+        Self::build(len, |w| {
+            let mut last = 0;
+            for (start, m) in self.match_indices(from) {
+                w.push_str(&self[last..start]);
+                w.push_str(to);
+                last = start + m.len();
+            }
+            w.push_str(&self[last..]);
+        })
+    }
+
+    /// Returns this string with each character escaped as with
+    /// [`char::escape_debug`], like [`str::escape_debug`].
+    ///
+    /// If nothing in `self` needs escaping, this returns a clone of `self` and
+    /// does not allocate.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use arcstr::ArcStr;
+    /// let s = ArcStr::from("❤\n!");
+    /// assert_eq!(s.escape_debug_arc(), "❤\\n!");
+    ///
+    /// let nothing_to_escape = ArcStr::from("❤!");
+    /// assert!(ArcStr::ptr_eq(&nothing_to_escape, &nothing_to_escape.escape_debug_arc()));
+    /// ```
+    pub fn escape_debug_arc(&self) -> Self {
+        // Escaping only ever makes things longer, so if the length is the
+        // same, nothing was escaped.
+        let len = total_len(self.escape_debug().map(char::len_utf8));
+        if len == self.len() {
+            return self.clone();
+        }
+        Self::build(len, |w| {
+            for c in self.escape_debug() {
+                w.push_char(c);
+            }
+        })
     }
 }
 
 impl Clone for ArcStr {
     #[inline]
     fn clone(&self) -> Self {
-        let this = self.0.as_ptr();
+        let this = self.inner();
         unsafe {
             // debug_assert_eq!(memoffset::offset_of!(ThinInner, nonstatic), 0);
             // let nonstatic_p = this as *const _ as *const bool;
@@ -417,7 +1318,7 @@ impl Clone for ArcStr {
                 // > the object.
                 //
                 // See: https://doc.rust-lang.org/src/alloc/sync.rs.html#1073
-                let n = (*this).strong.fetch_add(1, Ordering::Relaxed);
+                let n = ThinInner::refcount(this).fetch_add(1, Ordering::Relaxed);
                 // Protect against aggressive leaking of Arcs causing us to overflow `strong`.
                 if n > (isize::MAX as usize) {
                     abort();
@@ -431,20 +1332,20 @@ impl Clone for ArcStr {
 impl Drop for ArcStr {
     #[inline]
     fn drop(&mut self) {
-        let this = self.0.as_ptr();
+        let this = self.inner();
         unsafe {
             if ThinInner::get_len_flags(this).is_static() {
                 return;
             }
-            if (*this).strong.fetch_sub(1, Ordering::Release) == 1 {
+            let strong = ThinInner::refcount(this);
+            if strong.fetch_sub(1, Ordering::Release) == 1 {
                 // `libstd` uses a full acquire fence here but notes that it's
                 // possibly overkill. `triomphe`/`servo_arc` some of firefox ref
                 // counting uses a load like this.
                 //
                 // These are morally equivalent for this case, the fence being a
                 // bit more obvious and the load having slightly better perf in
-                // some theoretical scenarios... but for our use case both seem
-                // unnecessary.
+                // some theoretical scenarios.
                 //
                 // The intention behind these is to synchronize with `Release`
                 // writes to `strong` that are happening on other threads. That
@@ -453,15 +1354,21 @@ impl Drop for ArcStr {
                 // about) from other threads which happened before the latest
                 // `Release` write to strong will become visible on this thread.
                 //
-                // The reason this feels unnecessary is that our data is
-                // entirely immutable outside `(*this).strong`. There are no
-                // writes we could possibly be interested in.
-                //
-                // That said, I'll keep (the cheaper variant of) it for now for
-                // easier auditing and such... an because I'm not 100% sure that
-                // changing the ordering here wouldn't require changing it for
-                // the fetch_sub above, or the fetch_add in `clone`...
-                let _ = (*this).strong.load(Ordering::Acquire);
+                // Our data isn't entirely immutable: a `UniqueArcStr` can
+                // write to the string, and to the ASCII bit of `len_flags`.
+                // Those writes can't race with anything here, because
+                // uniqueness is established first: `is_unique` only succeeds
+                // after an `Acquire` load sees a count of 1, which
+                // synchronizes with the `Release` decrements of every other
+                // reference that ever existed, so nothing else can be looking
+                // at the string while it's written. Any reference created
+                // afterwards is cloned from the `ArcStr` the `UniqueArcStr`
+                // turns back into, so it comes after the writes in program
+                // order. If such a reference is then dropped on another
+                // thread, its `Release` decrement, paired with this `Acquire`,
+                // is what makes the writes happen-before we free the memory.
+                // So both orderings need to stay as they are.
+                let _ = strong.load(Ordering::Acquire);
                 ThinInner::destroy_cold(this)
             }
         }
@@ -471,8 +1378,12 @@ impl Drop for ArcStr {
 // located in static data (as with empty string). is_static being false meanse
 // we are a normal arc-ed string.
 //
-// While `ArcStr` claims to hold a pointer to a `ThinInner`, for the static case
-// we actually are using a pointer to a `ThinInnerStatic`. These are the same
+// `ArcStr` holds a pointer to the `data` field of one of these (so that
+// accessing the data doesn't need to add anything to it), and reaches the
+// header by subtracting `DATA_OFFSET`.
+//
+// While we treat the header as a `ThinInner`, for the static case we actually
+// are using a pointer to a `ThinInnerStatic`. These are the same
 // except for the type of the refernce count field. The issue is: We kind of
 // need the static ones to not have any interior mutability, so that `const`s
 // can use them, and so that they may be stored in read-only memory.
@@ -509,28 +1420,196 @@ pub struct StaticArcStrInner<Buf> {
     pub data: Buf,
 }
 
+// Not public API, exists for `literal_concat!`. The macro wraps every argument
+// in one of these (inside a `ManuallyDrop`, since we aren't allowed to run
+// `ArcStr`'s destructor during const eval) and then uses whichever inherent
+// `as_bytes` applies to the argument's type.
+#[doc(hidden)]
+pub struct ConcatPiece<T>(pub ManuallyDrop<T>);
+
+impl ConcatPiece<ArcStr> {
+    #[inline]
+    pub const fn as_bytes(&self) -> &'static [u8] {
+        // Safety: `ManuallyDrop` is `repr(transparent)`.
+        let s = unsafe { &*(&self.0 as *const ManuallyDrop<ArcStr> as *const ArcStr) };
+        // The macro only calls this during const eval, where every `ArcStr` is
+        // static, but nothing stops other code from calling it at runtime.
+        match static_str(s) {
+            Some(s) => s.as_bytes(),
+            None => panic!("literal_concat! arguments must be static ArcStrs"),
+        }
+    }
+}
+
+impl ConcatPiece<&'static str> {
+    #[inline]
+    pub const fn as_bytes(&self) -> &'static [u8] {
+        let s = unsafe { *(&self.0 as *const ManuallyDrop<&'static str> as *const &'static str) };
+        s.as_bytes()
+    }
+}
+
+// Not public API, exists for `arcstr_newtype!`. Returns the contents of `s`
+// if it's static.
+#[doc(hidden)]
+pub const fn static_str(s: &ArcStr) -> Option<&'static str> {
+    unsafe {
+        if (*(s.0.as_ptr().sub(DATA_OFFSET) as *const LenFlags)).is_static() {
+            Some(core::str::from_utf8_unchecked(s.static_bytes()))
+        } else {
+            None
+        }
+    }
+}
+
+// Not public API, exists for `literal_concat!`.
+#[doc(hidden)]
+pub const fn concat_len(pieces: &[&[u8]]) -> usize {
+    let mut len = 0;
+    let mut i = 0;
+    while i < pieces.len() {
+        len += pieces[i].len();
+        i += 1;
+    }
+    len
+}
+
+// Not public API, exists for `literal_concat!`. `N` must be
+// `concat_len(pieces)`, which the macro guarantees.
+#[doc(hidden)]
+pub const fn concat_bytes<const N: usize>(pieces: &[&[u8]]) -> [u8; N] {
+    let mut out = [0u8; N];
+    let mut pos = 0;
+    let mut i = 0;
+    while i < pieces.len() {
+        let piece = pieces[i];
+        let mut j = 0;
+        while j < piece.len() {
+            out[pos] = piece[j];
+            pos += 1;
+            j += 1;
+        }
+        i += 1;
+    }
+    assert!(pos == N);
+    out
+}
+
+// Not public API, exists for macros.
+#[doc(hidden)]
+pub const fn static_len_flags(bytes: &[u8]) -> usize {
+    // `<[u8]>::is_ascii` isn't usable in const fns on our MSRV.
+    let mut ascii = true;
+    let mut i = 0;
+    while i < bytes.len() {
+        ascii &= bytes[i] < 0x80;
+        i += 1;
+    }
+    assert!(
+        bytes.len() <= usize::MAX >> LenFlags::LEN_SHIFT,
+        "string is too long for an ArcStr",
+    );
+    LenFlags::from_len_static_raw(bytes.len(), true)
+        .with_ascii(ascii)
+        .0
+}
+
+// The low bit is clear for static strings and set for all others. The next bit
+// is set for strings whose data lives in an `ExternalInner`'s owner, the one
+// after that for strings from `ArcStr::from_str_padded`, the one after that for
+// strings which are entirely ASCII, and the one after that for strings carved
+// out of an `ArcStrArena` block. The remaining bits hold the length.
+//
+// The ASCII bit is computed when the string is created, and never changes
+// afterwards (with the exception of `UniqueArcStr`, which recomputes it).
 #[derive(Clone, Copy)]
 #[repr(transparent)]
 struct LenFlags(usize);
 
 impl LenFlags {
-    const EMPTY_STATIC: LenFlags = LenFlags(0);
+    const EMPTY_STATIC: LenFlags = LenFlags(Self::ASCII_BIT);
+    const NONSTATIC_BIT: usize = 0b01;
+    const EXTERNAL_BIT: usize = 0b10;
+    const PADDED_BIT: usize = 0b100;
+    const ASCII_BIT: usize = 0b1000;
+    const ARENA_BIT: usize = 0b10000;
+    const LEN_SHIFT: u32 = 5;
+
     #[inline]
     const fn len(self) -> usize {
-        self.0 >> 1
+        self.0 >> Self::LEN_SHIFT
     }
     #[inline]
     const fn is_static(self) -> bool {
-        (self.0 & 1) == 0
+        (self.0 & Self::NONSTATIC_BIT) == 0
+    }
+    #[inline]
+    const fn is_external(self) -> bool {
+        (self.0 & Self::EXTERNAL_BIT) != 0
+    }
+    #[inline]
+    const fn is_padded(self) -> bool {
+        (self.0 & Self::PADDED_BIT) != 0
+    }
+    #[inline]
+    const fn is_arena(self) -> bool {
+        (self.0 & Self::ARENA_BIT) != 0
+    }
+    #[inline]
+    const fn is_ascii(self) -> bool {
+        (self.0 & Self::ASCII_BIT) != 0
+    }
+    #[inline]
+    const fn with_ascii(self, ascii: bool) -> Self {
+        Self(self.0 & !Self::ASCII_BIT | (ascii as usize * Self::ASCII_BIT))
     }
 
     #[inline]
     fn from_len_static(l: usize, is_static: bool) -> Option<Self> {
-        l.checked_mul(2).map(|l| Self(l | (!is_static as usize)))
+        if l > (usize::MAX >> Self::LEN_SHIFT) {
+            None
+        } else {
+            Some(Self::from_len_static_raw(l, is_static))
+        }
     }
     #[inline]
     const fn from_len_static_raw(l: usize, is_static: bool) -> Self {
-        Self(l << 1 | (!is_static as usize))
+        Self(l << Self::LEN_SHIFT | (!is_static as usize))
+    }
+    #[inline]
+    fn from_len_external(l: usize) -> Option<Self> {
+        Self::from_len_static(l, false).map(|lf| Self(lf.0 | Self::EXTERNAL_BIT))
+    }
+    #[inline]
+    fn from_len_padded(l: usize) -> Option<Self> {
+        Self::from_len_static(l, false).map(|lf| Self(lf.0 | Self::PADDED_BIT))
+    }
+    #[inline]
+    fn from_len_arena(l: usize) -> Option<Self> {
+        Self::from_len_static(l, false).map(|lf| Self(lf.0 | Self::ARENA_BIT))
+    }
+}
+
+// The header for strings created by `ArcStr::from_owner`. The first two fields
+// are the same as in `ThinInner`, and `data` is at the same offset as
+// `ThinInner::data`, but holds a pointer to the string data (which belongs to
+// `owner`), instead of the data itself.
+//
+// Code which doesn't know `O` works with this as an `ExternalInner<()>`, which
+// is fine since `owner` is the last field, and so nothing before it depends on
+// its type.
+#[repr(C, align(8))]
+struct ExternalInner<O> {
+    len_flags: LenFlags,
+    strong: AtomicUsize,
+    data: *const u8,
+    drop_fn: unsafe fn(*mut ThinInner),
+    owner: O,
+}
+
+impl<O> ExternalInner<O> {
+    unsafe fn destroy(p: *mut ThinInner) {
+        drop(Box::from_raw(p as *mut Self));
     }
 }
 
@@ -549,37 +1628,54 @@ const EMPTY_INNER: &ThinInnerStatic = &ThinInnerStatic {
     data: [],
 };
 
-const EMPTY: ArcStr =
-    ArcStr(unsafe { NonNull::new_unchecked(EMPTY_INNER as *const _ as *mut ThinInner) });
+const EMPTY: ArcStr = ArcStr(unsafe {
+    NonNull::new_unchecked((EMPTY_INNER as *const ThinInnerStatic as *mut u8).add(DATA_OFFSET))
+});
+
+// The offset of the string data from the start of the header. `ArcStr` points
+// at the data (or for `ExternalInner`, at the pointer to it), and gets to the
+// header by subtracting this.
+const DATA_OFFSET: usize = size_of::<ThinInnerStatic>();
+
+// The alignment of the data of strings from `ArcStr::from_str_padded`.
+const PADDED_ALIGN: usize = 32;
 
 impl ThinInner {
     fn allocate(data: &str) -> NonNull<Self> {
+        let num_bytes = data.len();
+        let ptr = Self::allocate_uninit(num_bytes);
+        unsafe {
+            core::ptr::copy_nonoverlapping(data.as_ptr(), Self::data_ptr(ptr.as_ptr()), num_bytes);
+            Self::set_ascii(ptr.as_ptr(), data.is_ascii());
+        }
+        ptr
+    }
+
+    // Allocates space for `num_bytes` bytes of string data, and initializes
+    // everything but the data itself. The caller is responsible for writing
+    // exactly `num_bytes` of valid UTF-8 before anybody reads it.
+    fn allocate_uninit(num_bytes: usize) -> NonNull<Self> {
         const ALIGN: usize = align_of::<ThinInner>();
 
-        let num_bytes = data.len();
         debug_assert_ne!(num_bytes, 0);
 
         let mo = memoffset::offset_of!(ThinInner, data);
         if num_bytes >= (isize::MAX as usize) - (mo + ALIGN) {
             alloc_overflow();
         }
+        let lf = LenFlags::from_len_static(num_bytes, false).unwrap_or_else(|| alloc_overflow());
 
         unsafe {
-            debug_assert!(Layout::from_size_align(num_bytes + mo, ALIGN).is_ok());
-            let layout = Layout::from_size_align_unchecked(num_bytes + mo, ALIGN);
-
-            let alloced = alloc::alloc::alloc(layout);
+            let layout = Self::layout_for(num_bytes);
+            let alloced = alloc_block(layout);
             if alloced.is_null() {
                 alloc::alloc::handle_alloc_error(layout);
             }
 
             let ptr = alloced as *mut ThinInner;
 
-            // we actually already checked this above...
-            debug_assert!(LenFlags::from_len_static(num_bytes, false).is_some());
-            let lf = LenFlags::from_len_static_raw(num_bytes, false);
             debug_assert_eq!(lf.len(), num_bytes);
-            debug_assert_eq!(lf.is_static(), false);
+            debug_assert!(!lf.is_static());
 
             core::ptr::write(&mut (*ptr).len_flags, lf);
             core::ptr::write(&mut (*ptr).strong, AtomicUsize::new(1));
@@ -594,30 +1690,141 @@ impl ThinInner {
             );
             debug_assert_eq!(&(*ptr).data as *const _ as *const u8, (*ptr).data.as_ptr());
 
-            core::ptr::copy_nonoverlapping(data.as_ptr(), alloced.add(mo), num_bytes);
+            NonNull::new_unchecked(ptr)
+        }
+    }
 
+    // Allocates a string for `ArcStr::from_str_padded`. The allocation is
+    // `PADDED_ALIGN`-aligned, and looks like:
+    //
+    // [unused] [padding: usize] [header: ThinInner] [data] [`padding` zeroes]
+    //
+    // where `data` starts `PADDED_ALIGN` bytes in.
+    fn allocate_padded(data: &str, padding: usize) -> NonNull<Self> {
+        let num_bytes = data.len();
+        let size = PADDED_ALIGN
+            .checked_add(num_bytes)
+            .and_then(|n| n.checked_add(padding))
+            .filter(|&n| n < (isize::MAX as usize) - PADDED_ALIGN)
+            .unwrap_or_else(|| alloc_overflow());
+        let lf = LenFlags::from_len_padded(num_bytes)
+            .unwrap_or_else(|| alloc_overflow())
+            .with_ascii(data.is_ascii());
+        unsafe {
+            let layout = Layout::from_size_align_unchecked(size, PADDED_ALIGN);
+            let alloced = alloc::alloc::alloc(layout);
+            if alloced.is_null() {
+                alloc::alloc::handle_alloc_error(layout);
+            }
+            let ptr = alloced.add(PADDED_ALIGN - DATA_OFFSET) as *mut ThinInner;
+            core::ptr::write(&mut (*ptr).len_flags, lf);
+            core::ptr::write(&mut (*ptr).strong, AtomicUsize::new(1));
+            core::ptr::write((ptr as *mut usize).sub(1), padding);
+            let dst = Self::data_ptr(ptr);
+            debug_assert_eq!(dst, alloced.add(PADDED_ALIGN));
+            core::ptr::copy_nonoverlapping(data.as_ptr(), dst, num_bytes);
+            core::ptr::write_bytes(dst.add(num_bytes), 0, padding);
             NonNull::new_unchecked(ptr)
         }
     }
+
+    // Returns the amount of padding after a string from `allocate_padded`.
+    #[inline]
+    unsafe fn padding(p: *const ThinInner) -> usize {
+        *(p as *const usize).sub(1)
+    }
+
+    #[inline]
+    unsafe fn data_ptr(p: *mut ThinInner) -> *mut u8 {
+        (p as *mut u8).add(memoffset::offset_of!(ThinInner, data))
+    }
     #[inline]
     unsafe fn get_len_flags(p: *const ThinInner) -> LenFlags {
         debug_assert_eq!(memoffset::offset_of!(ThinInner, len_flags), 0);
         *p.cast()
     }
+    // Update the ASCII bit in the header of `p`, which must be a non-static
+    // string that nobody else can currently see.
+    #[inline]
+    unsafe fn set_ascii(p: *mut ThinInner, ascii: bool) {
+        let lf = Self::get_len_flags(p);
+        debug_assert!(!lf.is_static());
+        (*p).len_flags = lf.with_ascii(ascii);
+    }
+
+    // Resize the allocation of a unique, non-static `p` to hold `new_len` bytes
+    // of data, updating the length in its header. The ASCII bit is kept as-is,
+    // so callers adding non-ASCII data must clear it.
+    unsafe fn realloc(p: NonNull<ThinInner>, new_len: usize) -> NonNull<Self> {
+        const ALIGN: usize = align_of::<ThinInner>();
+        let mo = memoffset::offset_of!(ThinInner, data);
+        if new_len >= (isize::MAX as usize) - (mo + ALIGN) {
+            alloc_overflow();
+        }
+        let lf = Self::get_len_flags(p.as_ptr());
+        let new_lf = LenFlags::from_len_static(new_len, false)
+            .unwrap_or_else(|| alloc_overflow())
+            .with_ascii(lf.is_ascii());
+        debug_assert!(!lf.is_static() && !lf.is_external());
+        let old_layout = Self::layout_for(lf.len());
+        let new_layout = Self::layout_for(new_len);
+        // With the `pool` feature, allocations are rounded up, so there may
+        // already be enough room.
+        let alloced = if new_layout.size() == old_layout.size() {
+            p.as_ptr() as *mut u8
+        } else {
+            alloc::alloc::realloc(p.as_ptr() as *mut u8, old_layout, new_layout.size())
+        };
+        if alloced.is_null() {
+            alloc::alloc::handle_alloc_error(new_layout);
+        }
+        let ptr = alloced as *mut ThinInner;
+        core::ptr::write(&mut (*ptr).len_flags, new_lf);
+        NonNull::new_unchecked(ptr)
+    }
+
+    // The reference count of the nonstatic string at `p`. This is `strong`,
+    // except for arena strings, which share their block's count.
+    #[inline]
+    unsafe fn refcount<'a>(p: *mut ThinInner) -> &'a AtomicUsize {
+        if Self::get_len_flags(p).is_arena() {
+            crate::arc_str_arena::block_refs(p as *const u8)
+        } else {
+            &(*p).strong
+        }
+    }
 
     #[cold]
     unsafe fn destroy_cold(p: *mut ThinInner) {
         let lf = Self::get_len_flags(p);
         debug_assert!(!lf.is_static());
+        if lf.is_external() {
+            let drop_fn = (*(p as *const ExternalInner<()>)).drop_fn;
+            return drop_fn(p);
+        }
+        if lf.is_arena() {
+            return crate::arc_str_arena::free_block(p as *mut u8);
+        }
+        if lf.is_padded() {
+            let size = PADDED_ALIGN + lf.len() + Self::padding(p);
+            let layout = Layout::from_size_align_unchecked(size, PADDED_ALIGN);
+            let alloced = (p as *mut u8).sub(PADDED_ALIGN - DATA_OFFSET);
+            return alloc::alloc::dealloc(alloced, layout);
+        }
         // debug_assert!((*p).nonstatic);
-        let len = lf.len();
-        let layout = {
-            let size = len + memoffset::offset_of!(ThinInner, data);
-            let align = align_of::<ThinInner>();
-            // debug_assert_eq!(Layout::from_size_align(size, align), Ok((*p).orig_layout));
-            Layout::from_size_align_unchecked(size, align)
-        };
-        alloc::alloc::dealloc(p as *mut _, layout);
+        dealloc_block(p as *mut _, Self::layout_for(lf.len()));
+    }
+
+    // The layout of the allocation for a (non-padded) string with `len` bytes
+    // of inline data.
+    #[inline]
+    fn layout_for(len: usize) -> Layout {
+        let size = len + memoffset::offset_of!(ThinInner, data);
+        #[cfg(feature = "pool")]
+        let size = crate::pool::block_size(size);
+        debug_assert!(Layout::from_size_align(size, align_of::<ThinInner>()).is_ok());
+        // Safety: callers have already checked `len` for overflow.
+        unsafe { Layout::from_size_align_unchecked(size, align_of::<ThinInner>()) }
     }
 }
 
@@ -627,13 +1834,57 @@ fn alloc_overflow() -> ! {
     panic!("overflow during Layout computation")
 }
 
+// If exactly one of `pieces` is non-empty and it's an `ArcStr`, returns it.
+fn only_arcstr<S: StrPiece>(pieces: &[S]) -> Option<&ArcStr> {
+    let mut nonempty = pieces.iter().filter(|p| !p.as_ref().is_empty());
+    match (nonempty.next(), nonempty.next()) {
+        (Some(p), None) => p.as_arcstr(),
+        _ => None,
+    }
+}
+
+#[inline]
+fn total_len(lens: impl Iterator<Item = usize>) -> usize {
+    lens.fold(0usize, |total, len| {
+        total.checked_add(len).unwrap_or_else(|| alloc_overflow())
+    })
+}
+
+// Writes string data into an `ArcStr` that's being constructed by
+// `ArcStr::build`. Only accepts whole `str`s (or `char`s), so the result is
+// guaranteed to be UTF-8 as long as every byte gets written.
+pub(crate) struct StrWriter {
+    ptr: *mut u8,
+    cap: usize,
+    pos: usize,
+}
+
+impl StrWriter {
+    #[inline]
+    pub(crate) fn push_str(&mut self, s: &str) {
+        assert!(
+            s.len() <= self.cap - self.pos,
+            "wrote too many bytes to an ArcStr"
+        );
+        unsafe {
+            core::ptr::copy_nonoverlapping(s.as_ptr(), self.ptr.add(self.pos), s.len());
+        }
+        self.pos += s.len();
+    }
+
+    #[inline]
+    pub(crate) fn push_char(&mut self, c: char) {
+        self.push_str(c.encode_utf8(&mut [0u8; 4]));
+    }
+}
+
 impl From<&str> for ArcStr {
     #[inline]
     fn from(s: &str) -> Self {
         if s.is_empty() {
             Self::new()
         } else {
-            Self(ThinInner::allocate(s))
+            Self::from_inner(ThinInner::allocate(s))
         }
     }
 }
@@ -695,21 +1946,21 @@ impl From<ArcStr> for alloc::sync::Arc<str> {
 impl From<alloc::rc::Rc<str>> for ArcStr {
     #[inline]
     fn from(s: alloc::rc::Rc<str>) -> Self {
-        let s: &str = &*s;
+        let s: &str = &s;
         Self::from(s)
     }
 }
 impl From<alloc::sync::Arc<str>> for ArcStr {
     #[inline]
     fn from(s: alloc::sync::Arc<str>) -> Self {
-        let s: &str = &*s;
+        let s: &str = &s;
         Self::from(s)
     }
 }
 impl<'a> From<Cow<'a, str>> for ArcStr {
     #[inline]
     fn from(s: Cow<'a, str>) -> Self {
-        let s: &str = &*s;
+        let s: &str = &s;
         Self::from(s)
     }
 }
@@ -744,6 +1995,78 @@ impl From<&ArcStr> for ArcStr {
     }
 }
 
+// These buffer the items (though not their contents) so that the length of the
+// result is known before allocating it. An iterator can only be walked once, so
+// the alternative would be to guess, and reallocate when the guess is wrong.
+impl<'a> core::iter::FromIterator<&'a str> for ArcStr {
+    fn from_iter<I: IntoIterator<Item = &'a str>>(iter: I) -> Self {
+        Self::concat(&iter.into_iter().collect::<Vec<_>>())
+    }
+}
+
+impl core::iter::FromIterator<String> for ArcStr {
+    fn from_iter<I: IntoIterator<Item = String>>(iter: I) -> Self {
+        Self::concat(&iter.into_iter().collect::<Vec<_>>())
+    }
+}
+
+impl core::iter::FromIterator<char> for ArcStr {
+    fn from_iter<I: IntoIterator<Item = char>>(iter: I) -> Self {
+        Self::from_chars(iter.into_iter())
+    }
+}
+
+impl core::iter::FromIterator<ArcStr> for ArcStr {
+    fn from_iter<I: IntoIterator<Item = ArcStr>>(iter: I) -> Self {
+        Self::concat(&iter.into_iter().collect::<Vec<_>>())
+    }
+}
+
+impl<'a> core::iter::FromIterator<&'a ArcStr> for ArcStr {
+    fn from_iter<I: IntoIterator<Item = &'a ArcStr>>(iter: I) -> Self {
+        Self::concat(&iter.into_iter().collect::<Vec<_>>())
+    }
+}
+
+/// A string which can be passed to [`ArcStr::concat`] and [`ArcStr::join`].
+///
+/// This is implemented for the standard string types, and for `ArcStr` and
+/// [`ArcCow`], which those functions can return a clone of
+/// rather than copying.
+pub trait StrPiece: AsRef<str> {
+    /// Returns this piece as an `ArcStr`, if it is (or holds) one.
+    #[inline]
+    fn as_arcstr(&self) -> Option<&ArcStr> {
+        None
+    }
+}
+
+impl StrPiece for str {}
+impl StrPiece for String {}
+impl StrPiece for Box<str> {}
+impl StrPiece for Cow<'_, str> {}
+
+impl StrPiece for ArcStr {
+    #[inline]
+    fn as_arcstr(&self) -> Option<&ArcStr> {
+        Some(self)
+    }
+}
+
+impl StrPiece for ArcCow<'_> {
+    #[inline]
+    fn as_arcstr(&self) -> Option<&ArcStr> {
+        ArcCow::as_arcstr(self)
+    }
+}
+
+impl<T: ?Sized + StrPiece> StrPiece for &T {
+    #[inline]
+    fn as_arcstr(&self) -> Option<&ArcStr> {
+        T::as_arcstr(self)
+    }
+}
+
 impl core::fmt::Debug for ArcStr {
     #[inline]
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
@@ -764,6 +2087,7 @@ impl PartialEq for ArcStr {
         ArcStr::ptr_eq(self, o) || PartialEq::eq(self.as_str(), o.as_str())
     }
     #[inline]
+    #[allow(clippy::partialeq_ne_impl)]
     fn ne(&self, o: &Self) -> bool {
         !ArcStr::ptr_eq(self, o) && PartialEq::ne(self.as_str(), o.as_str())
     }
@@ -779,6 +2103,7 @@ macro_rules! impl_peq {
                 PartialEq::eq(&self[..], &s[..])
             }
             #[inline]
+            #[allow(clippy::partialeq_ne_impl)]
             fn ne(&self, s: &$b) -> bool {
                 PartialEq::ne(&self[..], &s[..])
             }
@@ -803,7 +2128,7 @@ impl_peq! {
 impl PartialOrd for ArcStr {
     #[inline]
     fn partial_cmp(&self, s: &Self) -> Option<core::cmp::Ordering> {
-        Some(self.as_str().cmp(s.as_str()))
+        Some(self.cmp(s))
     }
 }
 
@@ -874,7 +2199,7 @@ impl core::str::FromStr for ArcStr {
 #[cold]
 #[inline(never)]
 #[cfg(not(feature = "std"))]
-fn abort() -> ! {
+pub(crate) fn abort() -> ! {
     struct PanicOnDrop;
     impl Drop for PanicOnDrop {
         fn drop(&mut self) {
@@ -886,7 +2211,7 @@ fn abort() -> ! {
 }
 
 #[cfg(feature = "std")]
-use std::process::abort;
+pub(crate) use std::process::abort;
 
 #[cfg(test)]
 mod test {
@@ -906,6 +2231,36 @@ mod test {
             memoffset::offset_of!(ThinInner, data),
             memoffset::offset_of!(ThinInnerStatic, data),
         );
+        assert_eq!(memoffset::offset_of!(ThinInner, data), DATA_OFFSET);
+    }
+
+    #[test]
+    fn verify_external_offsets() {
+        fn external_layout_check<O>() {
+            assert_eq!(
+                memoffset::offset_of!(ThinInner, len_flags),
+                memoffset::offset_of!(ExternalInner<O>, len_flags),
+            );
+            assert_eq!(
+                memoffset::offset_of!(ThinInner, strong),
+                memoffset::offset_of!(ExternalInner<O>, strong),
+            );
+            assert_eq!(
+                memoffset::offset_of!(ThinInner, data),
+                memoffset::offset_of!(ExternalInner<O>, data),
+            );
+            assert_eq!(
+                memoffset::offset_of!(ExternalInner<()>, drop_fn),
+                memoffset::offset_of!(ExternalInner<O>, drop_fn),
+            );
+            assert!(align_of::<ExternalInner<O>>() >= align_of::<ThinInner>());
+        }
+        external_layout_check::<()>();
+        external_layout_check::<u8>();
+        external_layout_check::<String>();
+        external_layout_check::<Box<str>>();
+        external_layout_check::<[u8; 100]>();
+        external_layout_check::<u128>();
     }
 
     #[test]
@@ -0,0 +1,196 @@
+use crate::arc_str::{AtomicUsize, Ordering};
+use crate::ArcStr;
+use core::alloc::Layout;
+use core::ptr::NonNull;
+
+// The size (and alignment) of each block. Since blocks are aligned to their
+// size, a string can find its block by rounding its address down.
+const BLOCK_SIZE: usize = 64 * 1024;
+// Strings longer than this get their own allocation, to avoid wasting too much
+// of a block when they don't fit in the current one.
+const MAX_ARENA_LEN: usize = BLOCK_SIZE / 4;
+
+// The start of each block. The rest of the block is filled with strings, each
+// of which looks like a normal `ArcStr` allocation, except that its `strong`
+// count is unused.
+#[repr(C, align(8))]
+struct BlockHeader {
+    // The number of references to strings in the block, plus one if it's the
+    // current block of an `ArcStrArena`. Cloning or dropping an arena string
+    // updates this, rather than a count of its own.
+    refs: AtomicUsize,
+}
+
+/// An allocator which carves many [`ArcStr`]s out of a few large blocks.
+///
+/// Strings from an arena behave exactly like any other `ArcStr`: they can be
+/// cloned, sent to other threads, and outlive the arena. The difference is
+/// that instead of each string having its own heap allocation, they're packed
+/// together into 64KiB blocks, which are freed once every string in them has
+/// been dropped. This makes creating a large number of strings which live for
+/// about as long as each other (say, the words of a dictionary) much cheaper.
+///
+/// The tradeoff is that a single string that's kept around keeps its whole
+/// block alive. Strings longer than 16KiB aren't put in blocks, and are
+/// allocated normally.
+///
+/// Arena strings don't have a reference count of their own: cloning or
+/// dropping one updates the count for its block instead, so
+/// [`ArcStr::strong_count`] returns `None` for them.
+///
+/// Since their memory isn't theirs alone, arena strings can't be mutated by
+/// [`ArcStr::try_unique`], or grown in place by [`ArcStr::append`] and
+/// [`ArcStr::prepend`], which copy them instead.
+///
+/// # Examples
+///
+/// ```
+/// # use arcstr::{ArcStr, ArcStrArena};
+/// let mut arena = ArcStrArena::new();
+/// let words: Vec<ArcStr> = "the quick brown fox"
+///     .split(' ')
+///     .map(|w| arena.alloc(w))
+///     .collect();
+/// drop(arena);
+/// assert_eq!(words, ["the", "quick", "brown", "fox"]);
+/// // The words are counted by their block, not individually.
+/// assert_eq!(ArcStr::strong_count(&words[0]), None);
+/// ```
+pub struct ArcStrArena {
+    // The block we're currently allocating from, if any, which we hold a
+    // reference to.
+    block: Option<NonNull<u8>>,
+    // The offset of the unused space in `block`.
+    pos: usize,
+}
+
+// Safety: we only hold a (shared, atomically counted) reference to a block,
+// and `alloc` takes `&mut self`.
+unsafe impl Send for ArcStrArena {}
+unsafe impl Sync for ArcStrArena {}
+
+impl ArcStrArena {
+    /// Create a new arena. This doesn't allocate until the first string is
+    /// added.
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            block: None,
+            pos: 0,
+        }
+    }
+
+    /// Create an `ArcStr` with a copy of `s`, stored in the arena's current
+    /// block if there's room, and in a new block otherwise.
+    ///
+    /// Empty strings, and strings longer than 16KiB, are created as if by
+    /// `ArcStr::from(s)`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use arcstr::ArcStrArena;
+    /// let mut arena = ArcStrArena::new();
+    /// let s = arena.alloc("hello");
+    /// assert_eq!(s, "hello");
+    /// ```
+    pub fn alloc(&mut self, s: &str) -> ArcStr {
+        if s.is_empty() || s.len() > MAX_ARENA_LEN {
+            return ArcStr::from(s);
+        }
+        let size = ArcStr::arena_size(s.len());
+        let block = match self.block {
+            Some(b) if self.pos + size <= BLOCK_SIZE => b,
+            _ => self.new_block(),
+        };
+        unsafe {
+            // Relaxed is fine here for the same reason it is in
+            // `ArcStr::clone`: we already hold a reference to the block.
+            block_refs(block.as_ptr()).fetch_add(1, Ordering::Relaxed);
+            let dst = NonNull::new_unchecked(block.as_ptr().add(self.pos));
+            self.pos += size;
+            ArcStr::init_in_arena(dst, s)
+        }
+    }
+
+    // Replace our current block with a fresh one, and return it.
+    #[cold]
+    fn new_block(&mut self) -> NonNull<u8> {
+        if let Some(old) = self.block.take() {
+            unsafe { release_block(old.as_ptr()) };
+        }
+        let layout = block_layout();
+        let block = unsafe {
+            let p = alloc::alloc::alloc(layout);
+            if p.is_null() {
+                alloc::alloc::handle_alloc_error(layout);
+            }
+            core::ptr::write(
+                p as *mut BlockHeader,
+                BlockHeader {
+                    refs: AtomicUsize::new(1),
+                },
+            );
+            NonNull::new_unchecked(p)
+        };
+        self.block = Some(block);
+        self.pos = core::mem::size_of::<BlockHeader>();
+        block
+    }
+}
+
+impl Default for ArcStrArena {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for ArcStrArena {
+    fn drop(&mut self) {
+        if let Some(block) = self.block {
+            unsafe { release_block(block.as_ptr()) };
+        }
+    }
+}
+
+impl core::fmt::Debug for ArcStrArena {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("ArcStrArena")
+            .field("block_used", &self.block.map(|_| self.pos))
+            .finish()
+    }
+}
+
+#[inline]
+fn block_layout() -> Layout {
+    // Safety: `BLOCK_SIZE` is a power of two, and small.
+    unsafe { Layout::from_size_align_unchecked(BLOCK_SIZE, BLOCK_SIZE) }
+}
+
+#[inline]
+fn block_start(p: *const u8) -> *const u8 {
+    p.wrapping_sub(p as usize & (BLOCK_SIZE - 1))
+}
+
+// The reference count of the block containing `p`, which `ArcStr` uses for
+// strings in the block.
+#[inline]
+pub(crate) unsafe fn block_refs<'a>(p: *const u8) -> &'a AtomicUsize {
+    &(*(block_start(p) as *const BlockHeader)).refs
+}
+
+// Free the block containing `p`, once its count has dropped to zero.
+pub(crate) unsafe fn free_block(p: *mut u8) {
+    alloc::alloc::dealloc(block_start(p) as *mut u8, block_layout());
+}
+
+// Drop the arena's reference to the block containing `p`.
+unsafe fn release_block(p: *mut u8) {
+    let refs = block_refs(p);
+    if refs.fetch_sub(1, Ordering::Release) == 1 {
+        // See the comment in `ArcStr`'s `Drop` impl.
+        let _ = refs.load(Ordering::Acquire);
+        free_block(p);
+    }
+}
@@ -0,0 +1,421 @@
+use crate::arc_str::{abort, AtomicUsize, Ordering};
+use crate::ArcStr;
+use alloc::vec::Vec;
+use core::alloc::Layout;
+use core::convert::TryFrom;
+use core::iter::FromIterator;
+use core::mem::size_of;
+use core::ptr::NonNull;
+
+/// An immutable, reference-counted list of strings, stored in a single
+/// allocation.
+///
+/// This is an alternative to `Arc<[ArcStr]>` for large read-only lists of
+/// strings, such as the values of a column or a set of command line
+/// arguments. Rather than one allocation per string plus an array of
+/// pointers, an `ArcStrList` stores a refcount, a table of `u32` offsets, and
+/// the bytes of every string back to back, all in one allocation (similar to
+/// Apache Arrow's string arrays). Cloning it is just a refcount increment.
+///
+/// Strings in the list are accessed as `&str`s with [`get`](Self::get),
+/// indexing, or [`iter`](Self::iter). If you need one as an owned `ArcStr`,
+/// [`get_arc`](Self::get_arc) copies it out.
+///
+/// Because of the `u32` offsets, the strings in a list may have at most
+/// `u32::MAX` bytes in total.
+///
+/// # Examples
+///
+/// ```
+/// # use arcstr::ArcStrList;
+/// let list: ArcStrList = "alpha beta gamma".split(' ').collect();
+/// assert_eq!(list.len(), 3);
+/// assert_eq!(list.get(1), Some("beta"));
+/// assert_eq!(&list[2], "gamma");
+/// assert_eq!(list.get(3), None);
+///
+/// let shared = list.clone();
+/// assert!(ArcStrList::ptr_eq(&list, &shared));
+/// assert_eq!(shared.iter().collect::<Vec<_>>(), ["alpha", "beta", "gamma"]);
+/// ```
+#[repr(transparent)]
+pub struct ArcStrList(NonNull<ListInner>);
+
+// Safety: we're immutable and atomically refcounted, like `ArcStr`.
+unsafe impl Send for ArcStrList {}
+unsafe impl Sync for ArcStrList {}
+
+// The header of the allocation. It's followed by `count + 1` `u32` offsets
+// (the first of which is always 0), and then by the string data.
+//
+// The empty list is never allocated, and uses `EMPTY`, which is a
+// `ListInnerStatic`. As with `ArcStr`'s static strings, we must never touch
+// `strong` for it, which we ensure by checking `count` first.
+#[repr(C)]
+struct ListRepr<RcTy> {
+    strong: RcTy,
+    count: usize,
+    offsets: [u32; 0],
+}
+
+type ListInner = ListRepr<AtomicUsize>;
+type ListInnerStatic = ListRepr<usize>;
+
+#[repr(C)]
+struct EmptyList {
+    header: ListInnerStatic,
+    offsets: [u32; 1],
+}
+
+const EMPTY: &EmptyList = &EmptyList {
+    header: ListRepr {
+        strong: 0,
+        count: 0,
+        offsets: [],
+    },
+    offsets: [0],
+};
+
+impl ArcStrList {
+    /// Create an empty list. This doesn't allocate.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use arcstr::ArcStrList;
+    /// let list = ArcStrList::new();
+    /// assert!(list.is_empty());
+    /// ```
+    #[inline]
+    pub const fn new() -> Self {
+        Self(unsafe { NonNull::new_unchecked(EMPTY as *const EmptyList as *mut ListInner) })
+    }
+
+    /// Returns the number of strings in the list.
+    #[inline]
+    pub fn len(&self) -> usize {
+        unsafe { (*self.0.as_ptr()).count }
+    }
+
+    /// Returns true if the list contains no strings.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the string at `index`, or `None` if it's out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use arcstr::ArcStrList;
+    /// let list: ArcStrList = ["a", "", "c"].iter().collect();
+    /// assert_eq!(list.get(0), Some("a"));
+    /// assert_eq!(list.get(1), Some(""));
+    /// assert_eq!(list.get(3), None);
+    /// ```
+    #[inline]
+    pub fn get(&self, index: usize) -> Option<&str> {
+        if index >= self.len() {
+            return None;
+        }
+        let offsets = self.offsets();
+        let (start, end) = (offsets[index] as usize, offsets[index + 1] as usize);
+        // Safety: the offsets are in bounds and non-decreasing, and each
+        // string was copied from a `&str`.
+        unsafe {
+            let bytes = core::slice::from_raw_parts(self.data_ptr().add(start), end - start);
+            Some(core::str::from_utf8_unchecked(bytes))
+        }
+    }
+
+    /// Returns a copy of the string at `index` as an `ArcStr`, or `None` if
+    /// it's out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use arcstr::{ArcStr, ArcStrList};
+    /// let list: ArcStrList = ["a", "b"].iter().collect();
+    /// let b: ArcStr = list.get_arc(1).unwrap();
+    /// drop(list);
+    /// assert_eq!(b, "b");
+    /// ```
+    #[inline]
+    pub fn get_arc(&self, index: usize) -> Option<ArcStr> {
+        self.get(index).map(ArcStr::from)
+    }
+
+    /// Returns an iterator over the strings in the list.
+    #[inline]
+    pub fn iter(&self) -> ArcStrListIter<'_> {
+        ArcStrListIter {
+            list: self,
+            front: 0,
+            back: self.len(),
+        }
+    }
+
+    /// Returns true if the two lists share the same allocation.
+    ///
+    /// Note that all empty lists are considered equal by this function.
+    #[inline]
+    pub fn ptr_eq(lhs: &Self, rhs: &Self) -> bool {
+        lhs.0 == rhs.0 || (lhs.is_empty() && rhs.is_empty())
+    }
+
+    #[inline]
+    fn offsets(&self) -> &[u32] {
+        unsafe {
+            let p = self.0.as_ptr();
+            core::slice::from_raw_parts((*p).offsets.as_ptr(), (*p).count + 1)
+        }
+    }
+
+    #[inline]
+    fn data_ptr(&self) -> *const u8 {
+        let count = self.len();
+        unsafe {
+            (self.0.as_ptr() as *const u8)
+                .add(size_of::<ListInner>() + (count + 1) * size_of::<u32>())
+        }
+    }
+
+    // Allocate a list with the given offsets (which must start at 0, and be
+    // non-decreasing) and string data (which must be UTF-8, with every offset
+    // on a char boundary).
+    fn from_parts(offsets: &[u32], data: &[u8]) -> Self {
+        debug_assert_eq!(offsets.first(), Some(&0));
+        debug_assert_eq!(offsets.last().map(|&o| o as usize), Some(data.len()));
+        let count = offsets.len() - 1;
+        if count == 0 {
+            return Self::new();
+        }
+        let layout = Self::layout(count, data.len());
+        unsafe {
+            let p = alloc::alloc::alloc(layout) as *mut ListInner;
+            if p.is_null() {
+                alloc::alloc::handle_alloc_error(layout);
+            }
+            core::ptr::write(&mut (*p).strong, AtomicUsize::new(1));
+            core::ptr::write(&mut (*p).count, count);
+            let offs = (*p).offsets.as_mut_ptr();
+            core::ptr::copy_nonoverlapping(offsets.as_ptr(), offs, offsets.len());
+            let this = Self(NonNull::new_unchecked(p));
+            core::ptr::copy_nonoverlapping(data.as_ptr(), this.data_ptr() as *mut u8, data.len());
+            this
+        }
+    }
+
+    fn layout(count: usize, data_len: usize) -> Layout {
+        (count + 1)
+            .checked_mul(size_of::<u32>())
+            .and_then(|n| n.checked_add(size_of::<ListInner>()))
+            .and_then(|n| n.checked_add(data_len))
+            .and_then(|n| Layout::from_size_align(n, core::mem::align_of::<ListInner>()).ok())
+            .unwrap_or_else(|| panic!("overflow during Layout computation"))
+    }
+}
+
+// Collects strings into the offsets and data of a new list.
+pub(crate) struct ListBuilder {
+    offsets: Vec<u32>,
+    data: Vec<u8>,
+}
+
+impl ListBuilder {
+    pub(crate) fn with_capacity(count: usize) -> Self {
+        let mut offsets = Vec::with_capacity(count + 1);
+        offsets.push(0);
+        Self {
+            offsets,
+            data: Vec::new(),
+        }
+    }
+
+    // Returns `None` if this would take the total size of the strings past
+    // `u32::MAX`.
+    pub(crate) fn try_push(&mut self, s: &str) -> Option<()> {
+        let end = u32::try_from(self.data.len() + s.len()).ok()?;
+        self.data.extend_from_slice(s.as_bytes());
+        self.offsets.push(end);
+        Some(())
+    }
+
+    pub(crate) fn push(&mut self, s: &str) {
+        self.try_push(s)
+            .unwrap_or_else(|| panic!("too much string data for an ArcStrList"))
+    }
+
+    pub(crate) fn finish(self) -> ArcStrList {
+        ArcStrList::from_parts(&self.offsets, &self.data)
+    }
+}
+
+impl Clone for ArcStrList {
+    #[inline]
+    fn clone(&self) -> Self {
+        if !self.is_empty() {
+            // See `ArcStr`'s `Clone` impl for why `Relaxed` is fine.
+            let n = unsafe { (*self.0.as_ptr()).strong.fetch_add(1, Ordering::Relaxed) };
+            if n > (isize::MAX as usize) {
+                abort();
+            }
+        }
+        Self(self.0)
+    }
+}
+
+impl Drop for ArcStrList {
+    #[inline]
+    fn drop(&mut self) {
+        if self.is_empty() {
+            return;
+        }
+        let p = self.0.as_ptr();
+        unsafe {
+            if (*p).strong.fetch_sub(1, Ordering::Release) == 1 {
+                // See `ArcStr`'s `Drop` impl.
+                let _ = (*p).strong.load(Ordering::Acquire);
+                let data_len = *self.offsets().last().unwrap() as usize;
+                alloc::alloc::dealloc(p as *mut u8, Self::layout((*p).count, data_len));
+            }
+        }
+    }
+}
+
+impl Default for ArcStrList {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S: AsRef<str>> FromIterator<S> for ArcStrList {
+    fn from_iter<I: IntoIterator<Item = S>>(iter: I) -> Self {
+        let iter = iter.into_iter();
+        let mut b = ListBuilder::with_capacity(iter.size_hint().0);
+        for s in iter {
+            b.push(s.as_ref());
+        }
+        b.finish()
+    }
+}
+
+impl core::ops::Index<usize> for ArcStrList {
+    type Output = str;
+    #[inline]
+    fn index(&self, index: usize) -> &str {
+        match self.get(index) {
+            Some(s) => s,
+            None => panic!(
+                "index out of bounds: the len is {} but the index is {}",
+                self.len(),
+                index
+            ),
+        }
+    }
+}
+
+impl core::fmt::Debug for ArcStrList {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl PartialEq for ArcStrList {
+    #[inline]
+    fn eq(&self, o: &Self) -> bool {
+        Self::ptr_eq(self, o) || self.iter().eq(o.iter())
+    }
+}
+
+impl Eq for ArcStrList {}
+
+impl<S: AsRef<str>> PartialEq<[S]> for ArcStrList {
+    #[inline]
+    fn eq(&self, o: &[S]) -> bool {
+        self.iter().eq(o.iter().map(AsRef::as_ref))
+    }
+}
+
+impl<S: AsRef<str>> PartialEq<Vec<S>> for ArcStrList {
+    #[inline]
+    fn eq(&self, o: &Vec<S>) -> bool {
+        *self == o[..]
+    }
+}
+
+impl PartialOrd for ArcStrList {
+    #[inline]
+    fn partial_cmp(&self, o: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(o))
+    }
+}
+
+impl Ord for ArcStrList {
+    #[inline]
+    fn cmp(&self, o: &Self) -> core::cmp::Ordering {
+        self.iter().cmp(o.iter())
+    }
+}
+
+impl core::hash::Hash for ArcStrList {
+    fn hash<H: core::hash::Hasher>(&self, h: &mut H) {
+        // Same as for a slice of `str`s.
+        h.write_usize(self.len());
+        for s in self {
+            s.hash(h);
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a ArcStrList {
+    type Item = &'a str;
+    type IntoIter = ArcStrListIter<'a>;
+    #[inline]
+    fn into_iter(self) -> ArcStrListIter<'a> {
+        self.iter()
+    }
+}
+
+/// An iterator over the strings in an [`ArcStrList`], returned by
+/// [`ArcStrList::iter`].
+#[derive(Clone, Debug)]
+pub struct ArcStrListIter<'a> {
+    list: &'a ArcStrList,
+    front: usize,
+    back: usize,
+}
+
+impl<'a> Iterator for ArcStrListIter<'a> {
+    type Item = &'a str;
+    #[inline]
+    fn next(&mut self) -> Option<&'a str> {
+        if self.front == self.back {
+            return None;
+        }
+        let s = self.list.get(self.front);
+        self.front += 1;
+        s
+    }
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let n = self.back - self.front;
+        (n, Some(n))
+    }
+}
+
+impl DoubleEndedIterator for ArcStrListIter<'_> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.front == self.back {
+            return None;
+        }
+        self.back -= 1;
+        self.list.get(self.back)
+    }
+}
+
+impl ExactSizeIterator for ArcStrListIter<'_> {}
+impl core::iter::FusedIterator for ArcStrListIter<'_> {}
@@ -0,0 +1,190 @@
+use crate::ArcStr;
+use core::marker::PhantomData;
+use core::ptr::NonNull;
+
+/// A borrowed [`ArcStr`], which is `Copy` and the size of a pointer.
+///
+/// Passing an `&ArcStr` around means two indirections to get at the string
+/// data, and passing an `ArcStr` by value costs an atomic increment (and later,
+/// decrement). An `ArcStrRef<'a>` points at the same thing an `ArcStr` does,
+/// but doesn't own a reference, so it's free to create and copy, and has the
+/// same cost to access as an `ArcStr`.
+///
+/// If it turns out you do need to keep the string, [`ArcStrRef::upgrade`]
+/// gives you an `ArcStr` to it, which costs the same as `ArcStr::clone`.
+///
+/// These are created with [`ArcStr::borrow_ref`] (or `From<&ArcStr>`). This
+/// is similar to `ArcBorrow` from the `triomphe` crate.
+///
+/// # Examples
+///
+/// ```
+/// # use arcstr::{ArcStr, ArcStrRef};
+/// let s = ArcStr::from("abc");
+/// let r: ArcStrRef<'_> = s.borrow_ref();
+/// let r2 = r;
+/// assert_eq!(r, "abc");
+/// assert_eq!(r2.len(), 3);
+/// assert_eq!(ArcStr::strong_count(&s), Some(1));
+///
+/// let owned: ArcStr = r.upgrade();
+/// assert!(ArcStr::ptr_eq(&owned, &s));
+/// assert_eq!(ArcStr::strong_count(&s), Some(2));
+/// ```
+#[repr(transparent)]
+#[derive(Clone, Copy)]
+pub struct ArcStrRef<'a> {
+    // Same as the pointer in the `ArcStr` we borrow from.
+    ptr: NonNull<()>,
+    _marker: PhantomData<&'a ArcStr>,
+}
+
+// Safety: we're an `&ArcStr`, which is `Send` and `Sync`.
+unsafe impl Send for ArcStrRef<'_> {}
+unsafe impl Sync for ArcStrRef<'_> {}
+
+impl<'a> ArcStrRef<'a> {
+    // Safety: `ptr` must have come from an `ArcStr` which outlives `'a`.
+    #[inline]
+    pub(crate) unsafe fn from_raw(ptr: NonNull<()>) -> Self {
+        Self {
+            ptr,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Get an `ArcStr` for this string, incrementing its reference count.
+    #[inline]
+    pub fn upgrade(self) -> ArcStr {
+        self.as_arcstr().clone()
+    }
+
+    /// Returns a reference to this as if it were an `ArcStr`.
+    #[inline]
+    pub fn as_arcstr(&self) -> &ArcStr {
+        // Safety: both `Self` and `ArcStr` are `repr(transparent)` wrappers
+        // around the same pointer, and the `ArcStr` we came from is alive for
+        // at least `'a`. We never drop what we return.
+        unsafe { &*(self as *const Self as *const ArcStr) }
+    }
+
+    /// Extract a string slice containing our data.
+    ///
+    /// Unlike `Deref`, this returns a string which lives as long as the
+    /// `ArcStr` we borrowed from, rather than as long as `self`.
+    #[inline]
+    pub fn as_str(self) -> &'a str {
+        // Safety: the data is owned by the `ArcStr` we borrowed from, which
+        // is alive for `'a`.
+        unsafe { &*(self.as_arcstr().as_str() as *const str) }
+    }
+
+    /// Returns true if the two point to the same allocation, as with
+    /// [`ArcStr::ptr_eq`].
+    #[inline]
+    pub fn ptr_eq(lhs: Self, rhs: Self) -> bool {
+        lhs.ptr == rhs.ptr
+    }
+}
+
+impl core::ops::Deref for ArcStrRef<'_> {
+    type Target = str;
+    #[inline]
+    fn deref(&self) -> &str {
+        self.as_arcstr().as_str()
+    }
+}
+
+impl<'a> From<&'a ArcStr> for ArcStrRef<'a> {
+    #[inline]
+    fn from(s: &'a ArcStr) -> Self {
+        s.borrow_ref()
+    }
+}
+
+impl<'a> From<ArcStrRef<'a>> for ArcStr {
+    #[inline]
+    fn from(s: ArcStrRef<'a>) -> Self {
+        s.upgrade()
+    }
+}
+
+impl core::fmt::Debug for ArcStrRef<'_> {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        core::fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl core::fmt::Display for ArcStrRef<'_> {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        core::fmt::Display::fmt(self.as_str(), f)
+    }
+}
+
+impl PartialEq for ArcStrRef<'_> {
+    #[inline]
+    fn eq(&self, o: &Self) -> bool {
+        self.as_arcstr() == o.as_arcstr()
+    }
+}
+
+impl Eq for ArcStrRef<'_> {}
+
+macro_rules! impl_peq {
+    (@one $a:ty, $b:ty) => {
+        impl<'a, 'b> PartialEq<$b> for $a {
+            #[inline]
+            fn eq(&self, s: &$b) -> bool {
+                PartialEq::eq(&self[..], &s[..])
+            }
+        }
+    };
+    ($(($a:ty, $b:ty),)+) => {$(
+        impl_peq!(@one $a, $b);
+        impl_peq!(@one $b, $a);
+    )+};
+}
+
+impl_peq! {
+    (ArcStrRef<'a>, str),
+    (ArcStrRef<'a>, &'b str),
+    (ArcStrRef<'a>, alloc::string::String),
+    (ArcStrRef<'a>, ArcStr),
+}
+
+impl PartialOrd for ArcStrRef<'_> {
+    #[inline]
+    fn partial_cmp(&self, s: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(s))
+    }
+}
+
+impl Ord for ArcStrRef<'_> {
+    #[inline]
+    fn cmp(&self, s: &Self) -> core::cmp::Ordering {
+        self.as_str().cmp(s.as_str())
+    }
+}
+
+impl core::hash::Hash for ArcStrRef<'_> {
+    #[inline]
+    fn hash<H: core::hash::Hasher>(&self, h: &mut H) {
+        self.as_str().hash(h)
+    }
+}
+
+impl AsRef<str> for ArcStrRef<'_> {
+    #[inline]
+    fn as_ref(&self) -> &str {
+        self
+    }
+}
+
+impl core::borrow::Borrow<str> for ArcStrRef<'_> {
+    #[inline]
+    fn borrow(&self) -> &str {
+        self
+    }
+}
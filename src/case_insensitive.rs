@@ -0,0 +1,377 @@
+use crate::ArcStr;
+use alloc::string::String;
+use core::cmp::Ordering;
+use core::hash::{Hash, Hasher};
+
+/// An [`ArcStr`] which compares, orders and hashes ignoring ASCII case.
+///
+/// This is intended for things like HTTP header names or SQL identifiers,
+/// which are case-insensitive, but only for ASCII letters. The original
+/// casing is kept, and is what you get back from `Display`, `Debug`,
+/// [`as_str`](Self::as_str), and so on.
+///
+/// It implements `Borrow<AsciiCaseInsensitiveStr>`, which allows maps and sets
+/// keyed by `AsciiCaseInsensitive` to be searched with a `&str` without
+/// allocating, via [`AsciiCaseInsensitiveStr::new`].
+///
+/// [`AsciiCaseInsensitive::new`] is a `const fn`, so constants can be created
+/// from [`literal_arcstr!`](crate::literal_arcstr) or
+/// [`literal_concat!`](crate::literal_concat).
+///
+/// # Examples
+///
+/// ```
+/// # use arcstr::{AsciiCaseInsensitive, AsciiCaseInsensitiveStr};
+/// use std::collections::HashMap;
+///
+/// const CONTENT_TYPE: AsciiCaseInsensitive =
+///     AsciiCaseInsensitive::new(arcstr::literal_concat!("Content-Type"));
+///
+/// let mut headers = HashMap::new();
+/// headers.insert(CONTENT_TYPE, "text/plain");
+///
+/// let key = AsciiCaseInsensitiveStr::new("content-type");
+/// assert_eq!(headers.get(key), Some(&"text/plain"));
+///
+/// let (k, _) = headers.get_key_value(key).unwrap();
+/// assert_eq!(k.as_str(), "Content-Type");
+/// ```
+#[derive(Clone, Default)]
+#[repr(transparent)]
+pub struct AsciiCaseInsensitive(ArcStr);
+
+/// The borrowed form of [`AsciiCaseInsensitive`], a `str` which compares,
+/// orders and hashes ignoring ASCII case.
+///
+/// This is to `AsciiCaseInsensitive` as `str` is to `String`, and exists so
+/// that collections of `AsciiCaseInsensitive` can be searched using a `&str`.
+#[repr(transparent)]
+pub struct AsciiCaseInsensitiveStr(str);
+
+/// An [`ArcStr`] which compares, orders and hashes ignoring Unicode case.
+///
+/// Strings are compared by their case folding, which is approximated by
+/// uppercasing and then lowercasing each `char`. This handles most of what
+/// you'd expect (e.g. `"ß"` matches `"SS"`, and `"ς"` matches `"Σ"`), but it's
+/// not locale-aware, and doesn't perform any normalization.
+///
+/// The original casing is kept, and is what you get back from `Display`,
+/// `Debug`, [`as_str`](Self::as_str), and so on. It implements
+/// `Borrow<UniCaseStr>`, which allows maps and sets keyed by `UniCaseArcStr`
+/// to be searched with a `&str` without allocating, via [`UniCaseStr::new`].
+///
+/// If you only need to ignore ASCII case, [`AsciiCaseInsensitive`] is faster.
+///
+/// # Examples
+///
+/// ```
+/// # use arcstr::{UniCaseArcStr, UniCaseStr};
+/// use std::collections::BTreeSet;
+///
+/// const STRASSE: UniCaseArcStr = UniCaseArcStr::new(arcstr::literal_concat!("Straße"));
+///
+/// let mut set = BTreeSet::new();
+/// set.insert(STRASSE);
+/// assert!(set.contains(UniCaseStr::new("STRASSE")));
+/// assert!(set.contains(UniCaseStr::new("straße")));
+/// assert!(!set.contains(UniCaseStr::new("strase")));
+/// ```
+#[derive(Clone, Default)]
+#[repr(transparent)]
+pub struct UniCaseArcStr(ArcStr);
+
+/// The borrowed form of [`UniCaseArcStr`], a `str` which compares, orders and
+/// hashes ignoring Unicode case.
+///
+/// This is to `UniCaseArcStr` as `str` is to `String`, and exists so that
+/// collections of `UniCaseArcStr` can be searched using a `&str`.
+#[repr(transparent)]
+pub struct UniCaseStr(str);
+
+mod ascii {
+    use super::*;
+
+    #[inline]
+    pub(super) fn eq(a: &str, b: &str) -> bool {
+        a.eq_ignore_ascii_case(b)
+    }
+
+    #[inline]
+    pub(super) fn cmp(a: &str, b: &str) -> Ordering {
+        let a = a.bytes().map(|b| b.to_ascii_lowercase());
+        let b = b.bytes().map(|b| b.to_ascii_lowercase());
+        a.cmp(b)
+    }
+
+    #[inline]
+    pub(super) fn hash<H: Hasher>(s: &str, h: &mut H) {
+        for b in s.bytes() {
+            h.write_u8(b.to_ascii_lowercase());
+        }
+        // Same as `str`'s `Hash` impl, avoids collisions in tuples and such.
+        h.write_u8(0xff);
+    }
+}
+
+mod unicode {
+    use super::*;
+
+    #[inline]
+    fn fold(s: &str) -> impl Iterator<Item = char> + '_ {
+        s.chars()
+            .flat_map(|c| c.to_uppercase().flat_map(char::to_lowercase))
+    }
+
+    #[inline]
+    pub(super) fn eq(a: &str, b: &str) -> bool {
+        // Non-ASCII chars can fold to ASCII ones (e.g. the Kelvin sign), so
+        // this is only valid when both are ASCII.
+        if a.is_ascii() && b.is_ascii() {
+            return a.eq_ignore_ascii_case(b);
+        }
+        fold(a).eq(fold(b))
+    }
+
+    #[inline]
+    pub(super) fn cmp(a: &str, b: &str) -> Ordering {
+        if a.is_ascii() && b.is_ascii() {
+            return super::ascii::cmp(a, b);
+        }
+        fold(a).cmp(fold(b))
+    }
+
+    #[inline]
+    pub(super) fn hash<H: Hasher>(s: &str, h: &mut H) {
+        for c in fold(s) {
+            h.write_u32(c as u32);
+        }
+        h.write_u8(0xff);
+    }
+}
+
+macro_rules! impl_case_insensitive {
+    ($Owned:ident, $Borrowed:ident, $fold:ident) => {
+        impl $Owned {
+            /// Wrap an `ArcStr`. This is a `const fn`, and can be used with
+            /// `literal_arcstr!` or `literal_concat!` to create constants.
+            #[inline]
+            pub const fn new(s: ArcStr) -> Self {
+                Self(s)
+            }
+
+            /// Returns the wrapped string, with its original casing.
+            #[inline]
+            pub fn as_str(&self) -> &str {
+                &self.0
+            }
+
+            /// Returns a reference to the wrapped `ArcStr`.
+            #[inline]
+            pub fn as_arcstr(&self) -> &ArcStr {
+                &self.0
+            }
+
+            /// Unwrap this into the `ArcStr` it holds.
+            #[inline]
+            pub fn into_arcstr(self) -> ArcStr {
+                self.0
+            }
+        }
+
+        impl $Borrowed {
+            /// Convert a `&str` into a reference to this type, for use as a
+            /// lookup key. This doesn't allocate.
+            #[inline]
+            pub fn new(s: &str) -> &Self {
+                // Safety: `Self` is a `repr(transparent)` wrapper around `str`.
+                unsafe { &*(s as *const str as *const Self) }
+            }
+
+            /// Returns the wrapped string, with its original casing.
+            #[inline]
+            pub fn as_str(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl core::ops::Deref for $Owned {
+            type Target = str;
+            #[inline]
+            fn deref(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl core::ops::Deref for $Borrowed {
+            type Target = str;
+            #[inline]
+            fn deref(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl AsRef<str> for $Owned {
+            #[inline]
+            fn as_ref(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl AsRef<str> for $Borrowed {
+            #[inline]
+            fn as_ref(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl AsRef<$Borrowed> for $Owned {
+            #[inline]
+            fn as_ref(&self) -> &$Borrowed {
+                $Borrowed::new(&self.0)
+            }
+        }
+
+        impl core::borrow::Borrow<$Borrowed> for $Owned {
+            #[inline]
+            fn borrow(&self) -> &$Borrowed {
+                $Borrowed::new(&self.0)
+            }
+        }
+
+        impl alloc::borrow::ToOwned for $Borrowed {
+            type Owned = $Owned;
+            #[inline]
+            fn to_owned(&self) -> $Owned {
+                $Owned(ArcStr::from(&self.0))
+            }
+        }
+
+        impl From<ArcStr> for $Owned {
+            #[inline]
+            fn from(s: ArcStr) -> Self {
+                Self(s)
+            }
+        }
+
+        impl From<&str> for $Owned {
+            #[inline]
+            fn from(s: &str) -> Self {
+                Self(ArcStr::from(s))
+            }
+        }
+
+        impl From<String> for $Owned {
+            #[inline]
+            fn from(s: String) -> Self {
+                Self(ArcStr::from(s))
+            }
+        }
+
+        impl From<&$Borrowed> for $Owned {
+            #[inline]
+            fn from(s: &$Borrowed) -> Self {
+                Self(ArcStr::from(&s.0))
+            }
+        }
+
+        impl From<$Owned> for ArcStr {
+            #[inline]
+            fn from(s: $Owned) -> Self {
+                s.0
+            }
+        }
+
+        impl<'a> From<&'a str> for &'a $Borrowed {
+            #[inline]
+            fn from(s: &'a str) -> Self {
+                $Borrowed::new(s)
+            }
+        }
+
+        impl core::fmt::Debug for $Owned {
+            #[inline]
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                core::fmt::Debug::fmt(self.as_str(), f)
+            }
+        }
+
+        impl core::fmt::Debug for $Borrowed {
+            #[inline]
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                core::fmt::Debug::fmt(&self.0, f)
+            }
+        }
+
+        impl core::fmt::Display for $Owned {
+            #[inline]
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                core::fmt::Display::fmt(self.as_str(), f)
+            }
+        }
+
+        impl core::fmt::Display for $Borrowed {
+            #[inline]
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                core::fmt::Display::fmt(&self.0, f)
+            }
+        }
+
+        impl_case_insensitive!(@cmp $Owned, $fold);
+        impl_case_insensitive!(@cmp $Borrowed, $fold);
+        impl_case_insensitive!(@peq $fold, $Owned, $Borrowed);
+        impl_case_insensitive!(@peq $fold, $Owned, str);
+        impl_case_insensitive!(@peq $fold, $Owned, &'a str);
+        impl_case_insensitive!(@peq $fold, $Owned, &'a $Borrowed);
+        impl_case_insensitive!(@peq $fold, $Borrowed, str);
+        impl_case_insensitive!(@peq $fold, &'a $Borrowed, str);
+    };
+    (@cmp $T:ty, $fold:ident) => {
+        impl PartialEq for $T {
+            #[inline]
+            fn eq(&self, o: &Self) -> bool {
+                $fold::eq(&self.0, &o.0)
+            }
+        }
+
+        impl Eq for $T {}
+
+        impl PartialOrd for $T {
+            #[inline]
+            fn partial_cmp(&self, o: &Self) -> Option<Ordering> {
+                Some(self.cmp(o))
+            }
+        }
+
+        impl Ord for $T {
+            #[inline]
+            fn cmp(&self, o: &Self) -> Ordering {
+                $fold::cmp(&self.0, &o.0)
+            }
+        }
+
+        impl Hash for $T {
+            #[inline]
+            fn hash<H: Hasher>(&self, h: &mut H) {
+                $fold::hash(&self.0, h)
+            }
+        }
+    };
+    (@peq $fold:ident, $a:ty, $b:ty) => {
+        impl<'a> PartialEq<$b> for $a {
+            #[inline]
+            fn eq(&self, o: &$b) -> bool {
+                $fold::eq(&self[..], &o[..])
+            }
+        }
+
+        impl<'a> PartialEq<$a> for $b {
+            #[inline]
+            fn eq(&self, o: &$a) -> bool {
+                $fold::eq(&self[..], &o[..])
+            }
+        }
+    };
+}
+
+impl_case_insensitive!(AsciiCaseInsensitive, AsciiCaseInsensitiveStr, ascii);
+impl_case_insensitive!(UniCaseArcStr, UniCaseStr, unicode);
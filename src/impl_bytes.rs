@@ -0,0 +1,43 @@
+use super::ArcStr;
+use bytes::Bytes;
+use core::convert::TryFrom;
+
+impl From<ArcStr> for Bytes {
+    /// Convert an `ArcStr` into a `Bytes` without copying.
+    ///
+    /// Static strings become a static `Bytes`. Otherwise, the `ArcStr` is kept
+    /// alive as the owner of the returned `Bytes`'s data.
+    #[inline]
+    fn from(s: ArcStr) -> Self {
+        match ArcStr::as_static(&s) {
+            Some(st) => Bytes::from_static(st.as_bytes()),
+            None => Bytes::from_owner(s),
+        }
+    }
+}
+
+impl TryFrom<Bytes> for ArcStr {
+    type Error = core::str::Utf8Error;
+
+    /// Convert a `Bytes` into an `ArcStr` without copying, as long as it
+    /// contains valid UTF-8.
+    ///
+    /// The `Bytes` is kept alive as the owner of the returned `ArcStr`'s data,
+    /// as with [`ArcStr::from_owner`].
+    #[inline]
+    fn try_from(b: Bytes) -> Result<Self, Self::Error> {
+        core::str::from_utf8(&b)?;
+        Ok(ArcStr::from_owner(Utf8Bytes(b)))
+    }
+}
+
+// A `Bytes` which is known to contain valid UTF-8.
+struct Utf8Bytes(Bytes);
+
+impl AsRef<str> for Utf8Bytes {
+    #[inline]
+    fn as_ref(&self) -> &str {
+        // Safety: checked in `TryFrom<Bytes> for ArcStr`.
+        unsafe { core::str::from_utf8_unchecked(&self.0) }
+    }
+}
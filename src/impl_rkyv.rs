@@ -0,0 +1,318 @@
+use super::{ArcStr, StaticSet};
+use core::ptr::NonNull;
+use rkyv::bytecheck::CheckBytes;
+use rkyv::de::{ErasedPtr, Pool, Pooling, PoolingState};
+use rkyv::munge::munge;
+use rkyv::rancor::{Fallible, Source, Strategy};
+use rkyv::rc::{ArchivedRc, Flavor, RcResolver};
+use rkyv::ser::{Sharing, Writer};
+use rkyv::{Archive, Deserialize, Place, Portable, Serialize};
+
+/// The archived form of an [`ArcStr`].
+///
+/// `ArcStr`s which point to the same data (that is, which are
+/// [`ArcStr::ptr_eq`]) are only written to the archive once, and deserializing
+/// them produces `ArcStr`s which share a single allocation again. This is the
+/// same thing rkyv does for `Arc<str>`, but uses a separate flavor so that the
+/// two can't be confused during validation.
+///
+/// Whether the string was static is archived too. Deserializing with a
+/// [`StaticSetPool`] turns static strings from its set back into static
+/// `ArcStr`s, without allocating.
+///
+/// This derefs to `str`, so the data can be used directly from the archive,
+/// without deserializing or copying it.
+#[repr(C)]
+pub struct ArchivedArcStr {
+    rc: ArchivedRc<str, ArcStrFlavor>,
+    is_static: bool,
+}
+
+// Safety: `ArchivedArcStr` is `repr(C)`, and its fields are both `Portable`.
+unsafe impl Portable for ArchivedArcStr {}
+
+// Safety: checks both fields, which is all there is to check.
+unsafe impl<C> CheckBytes<C> for ArchivedArcStr
+where
+    C: Fallible + ?Sized,
+    ArchivedRc<str, ArcStrFlavor>: CheckBytes<C>,
+    bool: CheckBytes<C>,
+{
+    unsafe fn check_bytes(value: *const Self, context: &mut C) -> Result<(), C::Error> {
+        ArchivedRc::check_bytes(core::ptr::addr_of!((*value).rc), context)?;
+        bool::check_bytes(core::ptr::addr_of!((*value).is_static), context)
+    }
+}
+
+impl ArchivedArcStr {
+    /// Returns the archived string.
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        self.rc.get()
+    }
+
+    /// Returns true if the string was static (see [`ArcStr::is_static`]) when
+    /// it was archived.
+    #[inline]
+    pub fn is_static(&self) -> bool {
+        self.is_static
+    }
+}
+
+/// The [`Flavor`] of the shared pointer inside an [`ArchivedArcStr`].
+pub struct ArcStrFlavor;
+
+impl Flavor for ArcStrFlavor {
+    const ALLOW_CYCLES: bool = false;
+}
+
+impl Archive for ArcStr {
+    type Archived = ArchivedArcStr;
+    type Resolver = RcResolver;
+
+    #[inline]
+    fn resolve(&self, resolver: Self::Resolver, out: Place<Self::Archived>) {
+        munge!(let ArchivedArcStr { rc, is_static } = out);
+        ArchivedRc::resolve_from_ref(self.as_str(), resolver, rc);
+        is_static.write(ArcStr::is_static(self));
+    }
+}
+
+impl<S> Serialize<S> for ArcStr
+where
+    S: Fallible + Writer + Sharing + ?Sized,
+    S::Error: Source,
+{
+    #[inline]
+    fn serialize(&self, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+        // Sharing is keyed on the address of the data, which is the same for
+        // every clone of an `ArcStr`.
+        ArchivedRc::<str, ArcStrFlavor>::serialize_from_ref(self.as_str(), serializer)
+    }
+}
+
+/// A deserializer which can turn archived static strings back into static
+/// [`ArcStr`]s.
+///
+/// This is implemented for rkyv's default [`Pool`], which doesn't know about
+/// any (so only the empty string comes back static), and for
+/// [`StaticSetPool`], which looks them up in a [`StaticSet`].
+pub trait StaticLookup {
+    /// Returns the static `ArcStr` equal to `s`, if there is one.
+    fn lookup_static(&self, s: &str) -> Option<ArcStr>;
+}
+
+impl StaticLookup for Pool {
+    #[inline]
+    fn lookup_static(&self, _: &str) -> Option<ArcStr> {
+        None
+    }
+}
+
+impl<T: StaticLookup + ?Sized, E> StaticLookup for Strategy<T, E> {
+    #[inline]
+    fn lookup_static(&self, s: &str) -> Option<ArcStr> {
+        T::lookup_static(self, s)
+    }
+}
+
+/// An rkyv deserializer which deserializes static strings to the matching
+/// entries of a [`StaticSet`].
+///
+/// Strings which weren't static when they were archived, or which aren't in
+/// the set, are deserialized the same as with rkyv's default [`Pool`].
+///
+/// # Example
+///
+/// ```
+/// # use arcstr::{literal_concat, ArcStr, ArchivedArcStr, StaticSetPool};
+/// use rkyv::rancor::Error;
+///
+/// arcstr::static_set! {
+///     static METHODS = ["GET", "POST"];
+/// }
+///
+/// let strs = vec![literal_concat!("GET"), ArcStr::from("GET")];
+/// let bytes = rkyv::to_bytes::<Error>(&strs).unwrap();
+/// let archived = rkyv::access::<rkyv::vec::ArchivedVec<ArchivedArcStr>, Error>(&bytes).unwrap();
+///
+/// let mut pool = StaticSetPool::new(&METHODS);
+/// let out: Vec<ArcStr> = rkyv::api::deserialize_using::<_, _, Error>(archived, &mut pool).unwrap();
+/// assert!(ArcStr::is_static(&out[0]));
+/// assert!(!ArcStr::is_static(&out[1]));
+/// ```
+pub struct StaticSetPool<'a, const N: usize> {
+    pool: Pool,
+    set: &'a StaticSet<N>,
+}
+
+impl<'a, const N: usize> StaticSetPool<'a, N> {
+    /// Create a deserializer which looks static strings up in `set`.
+    #[inline]
+    pub fn new(set: &'a StaticSet<N>) -> Self {
+        Self {
+            pool: Pool::new(),
+            set,
+        }
+    }
+}
+
+impl<const N: usize> StaticLookup for StaticSetPool<'_, N> {
+    #[inline]
+    fn lookup_static(&self, s: &str) -> Option<ArcStr> {
+        self.set.get(s).cloned()
+    }
+}
+
+impl<E: Source, const N: usize> Pooling<E> for StaticSetPool<'_, N> {
+    #[inline]
+    fn start_pooling(&mut self, address: usize) -> PoolingState {
+        Pooling::<E>::start_pooling(&mut self.pool, address)
+    }
+
+    #[inline]
+    unsafe fn finish_pooling(
+        &mut self,
+        address: usize,
+        ptr: ErasedPtr,
+        drop: unsafe fn(ErasedPtr),
+    ) -> Result<(), E> {
+        self.pool.finish_pooling(address, ptr, drop)
+    }
+}
+
+impl<D> Deserialize<ArcStr, D> for ArchivedArcStr
+where
+    D: Fallible + Pooling + StaticLookup + ?Sized,
+    D::Error: Source,
+{
+    fn deserialize(&self, deserializer: &mut D) -> Result<ArcStr, D::Error> {
+        let s = self.as_str();
+        if s.is_empty() {
+            return Ok(ArcStr::new());
+        }
+        if self.is_static {
+            if let Some(s) = deserializer.lookup_static(s) {
+                return Ok(s);
+            }
+        }
+        let address = s.as_ptr() as usize;
+        match deserializer.start_pooling(address) {
+            PoolingState::Started => {
+                let result = ArcStr::from(s);
+                let pooled = ArcStr::into_raw(result.clone());
+                unsafe {
+                    deserializer.finish_pooling(
+                        address,
+                        ErasedPtr::new(pooled.as_ptr()),
+                        drop_pooled,
+                    )?;
+                }
+                Ok(result)
+            }
+            PoolingState::Finished(ptr) => {
+                // The pool keeps its own reference, so we hand out a clone.
+                let pooled = unsafe {
+                    core::mem::ManuallyDrop::new(ArcStr::from_raw(NonNull::new_unchecked(
+                        ptr.data_address(),
+                    )))
+                };
+                Ok(ArcStr::clone(&pooled))
+            }
+            // Strings can't contain other shared pointers, so this can't be
+            // the result of a cycle... but there's no harm in being defensive.
+            PoolingState::Pending => Ok(ArcStr::from(s)),
+        }
+    }
+}
+
+unsafe fn drop_pooled(ptr: ErasedPtr) {
+    drop(ArcStr::from_raw(NonNull::new_unchecked(ptr.data_address())));
+}
+
+impl core::ops::Deref for ArchivedArcStr {
+    type Target = str;
+    #[inline]
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl AsRef<str> for ArchivedArcStr {
+    #[inline]
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl core::borrow::Borrow<str> for ArchivedArcStr {
+    #[inline]
+    fn borrow(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl core::fmt::Debug for ArchivedArcStr {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        core::fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl core::fmt::Display for ArchivedArcStr {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        core::fmt::Display::fmt(self.as_str(), f)
+    }
+}
+
+impl PartialEq for ArchivedArcStr {
+    #[inline]
+    fn eq(&self, o: &Self) -> bool {
+        self.as_str() == o.as_str()
+    }
+}
+
+impl Eq for ArchivedArcStr {}
+
+impl PartialOrd for ArchivedArcStr {
+    #[inline]
+    fn partial_cmp(&self, o: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(o))
+    }
+}
+
+impl Ord for ArchivedArcStr {
+    #[inline]
+    fn cmp(&self, o: &Self) -> core::cmp::Ordering {
+        self.as_str().cmp(o.as_str())
+    }
+}
+
+impl core::hash::Hash for ArchivedArcStr {
+    #[inline]
+    fn hash<H: core::hash::Hasher>(&self, h: &mut H) {
+        self.as_str().hash(h)
+    }
+}
+
+impl PartialEq<str> for ArchivedArcStr {
+    #[inline]
+    fn eq(&self, o: &str) -> bool {
+        self.as_str() == o
+    }
+}
+
+impl PartialEq<ArcStr> for ArchivedArcStr {
+    #[inline]
+    fn eq(&self, o: &ArcStr) -> bool {
+        self.as_str() == o.as_str()
+    }
+}
+
+impl PartialEq<ArchivedArcStr> for ArcStr {
+    #[inline]
+    fn eq(&self, o: &ArchivedArcStr) -> bool {
+        self.as_str() == o.as_str()
+    }
+}
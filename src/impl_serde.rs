@@ -1,4 +1,5 @@
-use super::ArcStr;
+use super::arc_str_list::ListBuilder;
+use super::{ArcCow, ArcStr, ArcStrList, StaticSet};
 use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 
 impl Serialize for ArcStr {
@@ -29,3 +30,146 @@ impl<'de> de::Visitor<'de> for ArcStrVisitor {
         }
     }
 }
+
+/// Deserializes a string into the matching static entry of the set, without
+/// allocating, or into a new `ArcStr` if there isn't one (the same as
+/// [`ArcStr::from_known`]).
+///
+/// # Example
+///
+/// ```
+/// # use arcstr::ArcStr;
+/// use serde::de::{DeserializeSeed, Deserializer};
+/// use serde::Deserialize;
+///
+/// arcstr::static_set! {
+///     static METHODS = ["GET", "POST"];
+/// }
+///
+/// fn method<'de, D: Deserializer<'de>>(d: D) -> Result<ArcStr, D::Error> {
+///     METHODS.deserialize(d)
+/// }
+///
+/// #[derive(Deserialize)]
+/// struct Request {
+///     #[serde(deserialize_with = "method")]
+///     method: ArcStr,
+/// }
+///
+/// let r: Request = serde_json::from_str(r#"{"method": "GET"}"#).unwrap();
+/// assert!(ArcStr::is_static(&r.method));
+/// ```
+impl<'de, const N: usize> de::DeserializeSeed<'de> for &StaticSet<N> {
+    type Value = ArcStr;
+    fn deserialize<D: Deserializer<'de>>(self, d: D) -> Result<ArcStr, D::Error> {
+        d.deserialize_str(StaticSetVisitor(self))
+    }
+}
+
+struct StaticSetVisitor<'a, const N: usize>(&'a StaticSet<N>);
+impl<'de, const N: usize> de::Visitor<'de> for StaticSetVisitor<'_, N> {
+    type Value = ArcStr;
+    fn expecting(&self, formatter: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        formatter.write_str("a string")
+    }
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        Ok(ArcStr::from_known(v, self.0))
+    }
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+        match core::str::from_utf8(v) {
+            Ok(s) => Ok(ArcStr::from_known(s, self.0)),
+            Err(_) => Err(de::Error::invalid_value(de::Unexpected::Bytes(v), &self)),
+        }
+    }
+}
+
+impl Serialize for ArcCow<'_> {
+    fn serialize<S: Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+        ser.serialize_str(self)
+    }
+}
+
+/// Borrows from the input when the deserializer allows it, and otherwise
+/// produces an `ArcStr`.
+impl<'de> Deserialize<'de> for ArcCow<'de> {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        d.deserialize_str(ArcCowVisitor)
+    }
+}
+
+struct ArcCowVisitor;
+impl<'de> de::Visitor<'de> for ArcCowVisitor {
+    type Value = ArcCow<'de>;
+    fn expecting(&self, formatter: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        formatter.write_str("a string")
+    }
+    fn visit_borrowed_str<E: de::Error>(self, v: &'de str) -> Result<Self::Value, E> {
+        Ok(ArcCow::borrowed(v))
+    }
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        Ok(ArcCow::owned(v.into()))
+    }
+    fn visit_borrowed_bytes<E: de::Error>(self, v: &'de [u8]) -> Result<Self::Value, E> {
+        match core::str::from_utf8(v) {
+            Ok(s) => Ok(ArcCow::borrowed(s)),
+            Err(_) => Err(de::Error::invalid_value(de::Unexpected::Bytes(v), &self)),
+        }
+    }
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+        ArcStrVisitor.visit_bytes(v).map(ArcCow::owned)
+    }
+}
+
+impl Serialize for ArcStrList {
+    fn serialize<S: Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+        ser.collect_seq(self)
+    }
+}
+
+/// Deserializes a sequence of strings, copying each one directly into the
+/// list's buffer.
+impl<'de> Deserialize<'de> for ArcStrList {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        d.deserialize_seq(ArcStrListVisitor)
+    }
+}
+
+struct ArcStrListVisitor;
+impl<'de> de::Visitor<'de> for ArcStrListVisitor {
+    type Value = ArcStrList;
+    fn expecting(&self, formatter: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        formatter.write_str("a sequence of strings")
+    }
+    fn visit_seq<A: de::SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut b = ListBuilder::with_capacity(seq.size_hint().unwrap_or(0).min(4096));
+        while seq.next_element_seed(PushStr(&mut b))?.is_some() {}
+        Ok(b.finish())
+    }
+}
+
+// Deserializes a string into a `ListBuilder`.
+struct PushStr<'a>(&'a mut ListBuilder);
+impl<'de> de::DeserializeSeed<'de> for PushStr<'_> {
+    type Value = ();
+    fn deserialize<D: Deserializer<'de>>(self, d: D) -> Result<(), D::Error> {
+        d.deserialize_str(self)
+    }
+}
+
+impl<'de> de::Visitor<'de> for PushStr<'_> {
+    type Value = ();
+    fn expecting(&self, formatter: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        formatter.write_str("a string")
+    }
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<(), E> {
+        self.0
+            .try_push(v)
+            .ok_or_else(|| de::Error::custom("too much string data for an ArcStrList"))
+    }
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<(), E> {
+        match core::str::from_utf8(v) {
+            Ok(s) => self.visit_str(s),
+            Err(_) => Err(de::Error::invalid_value(de::Unexpected::Bytes(v), &self)),
+        }
+    }
+}
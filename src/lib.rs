@@ -1,7 +1,7 @@
 //! Various implementations of `Arc<str>`-like types.
 //!
-//! Well, just the one at the moment: [`ArcStr`], which has the following
-//! benefits over `Arc<str>`:
+//! The main one is [`ArcStr`], which has the following benefits over
+//! `Arc<str>`:
 //!
 //! - Only a single pointer. Great for cases where you want to keep the data
 //!   structure lightweight or need to do some FFI stuff with it.
@@ -29,6 +29,52 @@
 //! - We don't support `Weak` references, which means the overhead of atomic
 //!   operations is lower.
 //!
+//! Most of the other types here are built on top of `ArcStr`, such as
+//! [`ArcCow`], [`ArcStrList`], [`ArcRope`], and the case-insensitive wrappers.
+//!
+//! ### Minimum supported Rust version
+//!
+//! The MSRV is 1.64, and is tested in CI. Raising it is not considered a
+//! breaking change.
+//!
+//! The `rkyv` feature is the exception, and needs Rust 1.85 (since rkyv and its
+//! dependencies do).
+//!
+//! ### Memory layout
+//!
+//! An `ArcStr` is a single pointer to the first byte of the string's data
+//! (except for strings from [`ArcStr::from_owner`], described below), so
+//! getting at the bytes doesn't need any pointer arithmetic. The header sits
+//! immediately before the data, at a negative offset, and is two words long:
+//!
+//! - The first word holds the length, shifted left past a few flag bits:
+//!
+//!   - The lowest bit is clear for static strings (from
+//!     [`literal_arcstr!`][crate::literal_arcstr] and friends), which are
+//!     never reference counted, and set for every other string.
+//!   - `EXTERNAL`: the string was created by [`ArcStr::from_owner`]. The header
+//!     is followed by a pointer to the owner's bytes (and the owner itself),
+//!     rather than by the bytes.
+//!   - `PADDED`: the string was created by [`ArcStr::from_str_padded`], and
+//!     its allocation has extra alignment and trailing zeroes, which is
+//!     recorded in a word before the header.
+//!   - `ASCII`: every byte of the string is ASCII. This is computed when the
+//!     string is created, which makes [`ArcStr::is_ascii`] and
+//!     [`ArcStr::char_count`] cheap.
+//!   - `ARENA`: the string lives in a block owned by an [`ArcStrArena`], and
+//!     is counted by the block, which is freed when its last string is
+//!     dropped.
+//!
+//! - The second word is the strong count, which is never touched for static
+//!   or arena strings.
+//!
+//! Since five bits of the first word go to flags, an `ArcStr` can be at most
+//! `usize::MAX >> 5` bytes long. That's a lot on 64-bit targets, but only about
+//! 128MiB on 32-bit ones. Creating a longer string at runtime panics, and a
+//! longer literal or included file is a compile error.
+//!
+//! None of this is part of the public API, and it may change.
+//!
 //! ### Planned or incomplete funtionality
 //!
 //! #### `Substr` Type
@@ -49,10 +95,49 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
 extern crate alloc;
+mod arc_cow;
+mod arc_rope;
+// `cfg(loom)` is set by hand (with RUSTFLAGS) to run the loom tests.
+#[allow(unexpected_cfgs)]
 mod arc_str;
+mod arc_str_arena;
+mod arc_str_list;
+mod arc_str_ref;
+mod case_insensitive;
+#[cfg(feature = "bytes")]
+mod impl_bytes;
+#[cfg(feature = "rkyv")]
+mod impl_rkyv;
 #[cfg(feature = "serde")]
 mod impl_serde;
-pub use arc_str::ArcStr;
+mod newtype;
+#[cfg(feature = "derive")]
+mod parse_variant_error;
+#[cfg(feature = "pool")]
+pub mod pool;
+#[cfg(feature = "serde")]
+pub mod serde;
+mod static_set;
+mod tagged_arc_str;
+mod unique_arc_str;
+pub use arc_cow::ArcCow;
+pub use arc_rope::{ArcRope, ArcRopeChunks};
+pub use arc_str::{ArcStr, StrPiece};
+pub use arc_str_arena::ArcStrArena;
+pub use arc_str_list::{ArcStrList, ArcStrListIter};
+pub use arc_str_ref::ArcStrRef;
+#[cfg(feature = "derive")]
+pub use arcstr_derive::ArcStrEnum;
+pub use case_insensitive::{
+    AsciiCaseInsensitive, AsciiCaseInsensitiveStr, UniCaseArcStr, UniCaseStr,
+};
+#[cfg(feature = "rkyv")]
+pub use impl_rkyv::{ArcStrFlavor, ArchivedArcStr, StaticLookup, StaticSetPool};
+#[cfg(feature = "derive")]
+pub use parse_variant_error::ParseVariantError;
+pub use static_set::StaticSet;
+pub use tagged_arc_str::{TaggedArcStr, TaggedArcStrRef};
+pub use unique_arc_str::UniqueArcStr;
 
 /// Create a const `ArcStr` from a (byte-string) literal. The resulting `ArcStr`
 /// require no heap allocation, can be freely cloned and used interchangeably
@@ -105,7 +190,7 @@ macro_rules! literal_arcstr {
         const BYTES: &[u8; LEN] = $bytes;
         const INNER: &$crate::private_::StaticArcStrInner<[u8; LEN]> =
             &$crate::private_::StaticArcStrInner {
-                len_flags: LEN << 1,
+                len_flags: $crate::private_::static_len_flags(BYTES),
                 count: 0,
                 data: *BYTES,
             };
@@ -113,8 +198,142 @@ macro_rules! literal_arcstr {
     }};
 }
 
+/// Create a const `ArcStr` by concatenating other `const` `ArcStr`s and string
+/// literals at compile time.
+///
+/// Each argument must be a constant expression which is either an `ArcStr`
+/// (for example, a `const` made with [`literal_arcstr!`] or another
+/// `literal_concat!`), or a `&'static str` (for example, a string literal or a
+/// `const` of type `&str`).
+///
+/// Like with [`literal_arcstr!`], the result requires no heap allocation and
+/// lives in the read-only data of your executable. Unlike `literal_arcstr!`,
+/// this macro is safe, since all of its inputs are already known to be valid
+/// UTF-8.
+///
+/// # Usage
+///
+/// ```
+/// # use arcstr::{ArcStr, literal_arcstr, literal_concat};
+/// const PREFIX: ArcStr = unsafe { literal_arcstr!(b"/api/v1") };
+/// const METRICS: ArcStr = literal_concat!(PREFIX, "/metrics");
+/// assert_eq!(METRICS, "/api/v1/metrics");
+/// assert!(ArcStr::is_static(&METRICS));
+///
+/// // `ArcStr`s and literals may be mixed freely, in any order.
+/// const SEP: &str = "/";
+/// const NESTED: ArcStr = literal_concat!(METRICS, SEP, "cpu", SEP, PREFIX);
+/// assert_eq!(NESTED, "/api/v1/metrics/cpu//api/v1");
+///
+/// // Or, just in normal expressions.
+/// assert_eq!(literal_concat!("Wow", "!"), "Wow!");
+/// ```
+#[macro_export]
+macro_rules! literal_concat {
+    ($($piece:expr),* $(,)?) => {{
+        const PIECES: &[&[u8]] = &[$(
+            $crate::private_::ConcatPiece($crate::private_::ManuallyDrop::new($piece)).as_bytes()
+        ),*];
+        const LEN: usize = $crate::private_::concat_len(PIECES);
+        const BYTES: [u8; LEN] = $crate::private_::concat_bytes::<LEN>(PIECES);
+        const INNER: &$crate::private_::StaticArcStrInner<[u8; LEN]> =
+            &$crate::private_::StaticArcStrInner {
+                len_flags: $crate::private_::static_len_flags(&BYTES),
+                count: 0,
+                data: BYTES,
+            };
+        // Safety: every piece was either a `&str` or an `ArcStr`, so the
+        // concatenation is valid UTF-8.
+        unsafe { $crate::ArcStr::new_static(INNER) }
+    }};
+}
+
+/// Embed the contents of a file as a const `ArcStr`, in the same way as
+/// [`include_str!`].
+///
+/// The path is interpreted relative to the file containing the macro
+/// invocation, the file is tracked as a dependency of the build (so changes to
+/// it trigger a rebuild), and it's a compile error if its contents are not
+/// valid UTF-8. These all come from `include_str!`, which this is built on.
+///
+/// This is a safe replacement for
+/// `unsafe { literal_arcstr!(include_bytes!("...")) }`, and has the same
+/// benefits: the result requires no heap allocation, and lives in the read-only
+/// data of your executable.
+///
+/// Like every `ArcStr`, the file can be at most `usize::MAX >> 5` bytes long,
+/// which is about 128MiB on 32-bit targets.
+///
+/// # Usage
+///
+/// ```
+/// # use arcstr::{ArcStr, include_arcstr};
+/// const LICENSE: ArcStr = include_arcstr!("../LICENSE-MIT");
+/// assert!(LICENSE.contains("Permission is hereby granted"));
+/// assert!(ArcStr::is_static(&LICENSE));
+/// ```
+#[macro_export]
+macro_rules! include_arcstr {
+    ($path:expr $(,)?) => {
+        $crate::literal_concat!(include_str!($path))
+    };
+}
+
+/// Define a [`StaticSet`] of const `ArcStr`s, with a perfect hash table built
+/// at compile time.
+///
+/// The entries are given in the same form as the arguments to
+/// [`literal_concat!`]: each one must be a constant expression which is either
+/// an `ArcStr` or a `&'static str`. Duplicate entries are a compile error.
+///
+/// This is best suited to small and medium-sized sets (up to a few thousand
+/// entries or so), since the table is built during const evaluation.
+///
+/// # Usage
+///
+/// ```
+/// # use arcstr::{ArcStr, literal_arcstr};
+/// const ERROR: ArcStr = unsafe { literal_arcstr!(b"error") };
+///
+/// arcstr::static_set! {
+///     /// Doc comments and other attributes are allowed.
+///     pub static LEVELS = [ERROR, "warn", "info", "debug", "trace"];
+/// }
+/// assert_eq!(LEVELS.len(), 5);
+/// assert_eq!(LEVELS.get("error"), Some(&ERROR));
+/// assert!(!LEVELS.contains("fatal"));
+///
+/// // As an expression, the type must be spelled out.
+/// const ON_OFF: arcstr::StaticSet<2> = arcstr::static_set!["on", "off"];
+/// assert!(ON_OFF.contains("on"));
+/// ```
+#[macro_export]
+macro_rules! static_set {
+    ($(#[$m:meta])* $vis:vis static $name:ident = [$($entry:expr),* $(,)?];) => {
+        $(#[$m])*
+        $vis static $name: $crate::StaticSet<{ <[&str]>::len(&[$(stringify!($entry)),*]) }> =
+            $crate::static_set![$($entry),*];
+    };
+    ($($entry:expr),* $(,)?) => {{
+        const SET: $crate::StaticSet<{ <[&str]>::len(&[$(stringify!($entry)),*]) }> =
+            // Safety: `literal_concat!` only produces static `ArcStr`s.
+            unsafe { $crate::private_::build_static_set([$($crate::literal_concat!($entry)),*]) };
+        SET
+    }};
+}
+
 // Not public API, exists for macros
 #[doc(hidden)]
 pub mod private_ {
-    pub use crate::arc_str::StaticArcStrInner;
+    pub use crate::arc_str::{
+        concat_bytes, concat_len, static_len_flags, static_str, ConcatPiece, StaticArcStrInner,
+    };
+    pub use crate::newtype::{NewtypeError, NewtypeErrorDisplay, NewtypeErrorFallback};
+    #[cfg(feature = "derive")]
+    pub use crate::parse_variant_error::parse_variant_error;
+    pub use crate::static_set::build_static_set;
+    #[cfg(feature = "serde")]
+    pub use ::serde;
+    pub use alloc::string::String;
+    pub use core::mem::ManuallyDrop;
 }
@@ -0,0 +1,456 @@
+/// Define a newtype around [`ArcStr`](crate::ArcStr) whose contents are checked
+/// by a validation function.
+///
+/// This is for the common pattern of types like `UserId(ArcStr)` or
+/// `Hostname(ArcStr)`, which are strings, but only some strings. The macro
+/// takes the struct definition, along with a validation function, which must
+/// have the signature `fn(&str) -> Result<(), Error>`, and the `Error` type it
+/// returns.
+///
+/// # Usage
+///
+/// ```
+/// use arcstr::{arcstr_newtype, ArcStr};
+///
+/// #[derive(Debug, PartialEq)]
+/// pub struct BadUserId;
+///
+/// fn check_user_id(s: &str) -> Result<(), BadUserId> {
+///     if !s.is_empty() && s.bytes().all(|b| b.is_ascii_alphanumeric()) {
+///         Ok(())
+///     } else {
+///         Err(BadUserId)
+///     }
+/// }
+///
+/// arcstr_newtype! {
+///     /// The ID of a user.
+///     pub struct UserId(ArcStr) where validate = check_user_id, error = BadUserId;
+/// }
+///
+/// let id = UserId::new("alice123").unwrap();
+/// assert_eq!(id, "alice123");
+/// assert_eq!(id.len(), 8); // via `Deref<Target = str>`
+/// assert_eq!(UserId::new("not valid!"), Err(BadUserId));
+/// assert!("".parse::<UserId>().is_err());
+///
+/// let s: ArcStr = id.into_arcstr();
+/// assert_eq!(s, "alice123");
+/// ```
+///
+/// The type gets:
+///
+/// - `new`, which validates anything convertible to an `ArcStr`, and the
+///   accessors `as_str`, `as_arcstr` and `into_arcstr`.
+/// - `Clone`, `Debug`, `Display`, `Deref<Target = str>`, `Borrow<str>`,
+///   `AsRef` to `str`, `[u8]` and `ArcStr`, and `Index` by byte ranges, all of
+///   which act on the string.
+/// - `Eq`, `Ord` and `Hash` by content, and `PartialEq` in both directions
+///   with `str`, `&str`, `String` and `ArcStr`.
+/// - `FromStr`, and `TryFrom<&str>`, `TryFrom<String>` and `TryFrom<ArcStr>`,
+///   all of which validate, and fail with the given error type.
+/// - `From<Newtype> for ArcStr`.
+///
+/// Other traits (such as `Default`, if there's a valid default) can be
+/// implemented by hand, using `as_str` or `as_arcstr`.
+///
+/// # Serde support
+///
+/// Add `serde` after the error type, and the type also gets `Serialize` (as a
+/// string) and `Deserialize`, which runs the validator, and reports failures
+/// using the error type's `Display` impl (or a generic message, if it doesn't
+/// have one). This requires arcstr's `serde` feature, and is a compile error
+/// without it.
+///
+/// ```
+/// # use arcstr::{arcstr_newtype, ArcStr};
+/// # pub struct BadUserId;
+/// # fn check_user_id(s: &str) -> Result<(), BadUserId> { Ok(()) }
+/// # #[cfg(feature = "serde")]
+/// arcstr_newtype! {
+///     pub struct UserId(ArcStr) where validate = check_user_id, error = BadUserId, serde;
+/// }
+/// ```
+///
+/// # Const instances
+///
+/// If the validator is a `const fn`, write `validate = const path`, and the
+/// type also gets a `const fn from_static(s: ArcStr) -> Self`. This accepts a
+/// static `ArcStr` (from [`literal_arcstr!`](crate::literal_arcstr),
+/// [`literal_concat!`](crate::literal_concat), etc), and panics if it's
+/// invalid, which is a compile error when used in a `const`.
+///
+/// ```
+/// use arcstr::{arcstr_newtype, literal_concat, ArcStr};
+///
+/// pub struct BadHostname;
+///
+/// const fn check_hostname(s: &str) -> Result<(), BadHostname> {
+///     let b = s.as_bytes();
+///     if b.is_empty() || b.len() > 253 {
+///         return Err(BadHostname);
+///     }
+///     let mut i = 0;
+///     while i < b.len() {
+///         if !(b[i].is_ascii_alphanumeric() || b[i] == b'-' || b[i] == b'.') {
+///             return Err(BadHostname);
+///         }
+///         i += 1;
+///     }
+///     Ok(())
+/// }
+///
+/// arcstr_newtype! {
+///     pub struct Hostname(ArcStr) where validate = const check_hostname, error = BadHostname;
+/// }
+///
+/// const LOCALHOST: Hostname = Hostname::from_static(literal_concat!("localhost"));
+/// assert_eq!(LOCALHOST, "localhost");
+/// assert!(ArcStr::is_static(LOCALHOST.as_arcstr()));
+/// ```
+///
+/// Invalid constants fail to compile:
+///
+/// ```compile_fail
+/// # use arcstr::{arcstr_newtype, literal_concat, ArcStr};
+/// # const fn check_hostname(s: &str) -> Result<(), ()> {
+/// #     if s.is_empty() { Err(()) } else { Ok(()) }
+/// # }
+/// # arcstr_newtype! {
+/// #     pub struct Hostname(ArcStr) where validate = const check_hostname, error = ();
+/// # }
+/// const EMPTY: Hostname = Hostname::from_static(literal_concat!(""));
+/// ```
+#[macro_export]
+macro_rules! arcstr_newtype {
+    (
+        $(#[$m:meta])*
+        $vis:vis struct $name:ident(ArcStr) where validate = const $validate:path, $($rest:tt)*
+    ) => {
+        $crate::arcstr_newtype! {
+            $(#[$m])*
+            $vis struct $name(ArcStr) where validate = $validate, $($rest)*
+        }
+
+        impl $name {
+            /// Wrap a static `ArcStr`, such as one from `literal_arcstr!`.
+            ///
+            /// # Panics
+            ///
+            /// If `s` is invalid, or isn't static. In a `const`, this is a
+            /// compile error.
+            $vis const fn from_static(s: $crate::ArcStr) -> Self {
+                let st = match $crate::private_::static_str(&s) {
+                    ::core::option::Option::Some(st) => st,
+                    ::core::option::Option::None => {
+                        ::core::panic!("`from_static` requires a static `ArcStr`")
+                    }
+                };
+                match $validate(st) {
+                    ::core::result::Result::Ok(()) => {}
+                    ::core::result::Result::Err(e) => {
+                        // The error might not be droppable in a const fn.
+                        let _e = ::core::mem::ManuallyDrop::new(e);
+                        ::core::panic!(::core::concat!(
+                            "invalid `",
+                            ::core::stringify!($name),
+                            "` passed to `from_static`"
+                        ))
+                    }
+                }
+                Self(s)
+            }
+        }
+    };
+    (
+        $(#[$m:meta])*
+        $vis:vis struct $name:ident(ArcStr) where validate = $validate:path, error = $err:ty, serde $(,)?;
+    ) => {
+        $crate::arcstr_newtype! {
+            $(#[$m])*
+            $vis struct $name(ArcStr) where validate = $validate, error = $err;
+        }
+
+        $crate::__arcstr_newtype_serde!($name);
+    };
+    (
+        $(#[$m:meta])*
+        $vis:vis struct $name:ident(ArcStr) where validate = $validate:path, error = $err:ty $(,)?;
+    ) => {
+        $(#[$m])*
+        #[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        #[repr(transparent)]
+        $vis struct $name($crate::ArcStr);
+
+        impl $name {
+            /// Validate `s`, and wrap it if it's valid.
+            #[inline]
+            $vis fn new(s: impl ::core::convert::Into<$crate::ArcStr>) -> ::core::result::Result<Self, $err> {
+                let s: $crate::ArcStr = s.into();
+                $validate(s.as_str())?;
+                ::core::result::Result::Ok(Self(s))
+            }
+
+            /// Returns the contents as a string slice.
+            #[inline]
+            $vis fn as_str(&self) -> &str {
+                self.0.as_str()
+            }
+
+            /// Returns the underlying `ArcStr`.
+            #[inline]
+            $vis fn as_arcstr(&self) -> &$crate::ArcStr {
+                &self.0
+            }
+
+            /// Convert into the underlying `ArcStr`.
+            #[inline]
+            $vis fn into_arcstr(self) -> $crate::ArcStr {
+                self.0
+            }
+        }
+
+        impl ::core::ops::Deref for $name {
+            type Target = str;
+            #[inline]
+            fn deref(&self) -> &str {
+                self.0.as_str()
+            }
+        }
+
+        impl ::core::convert::AsRef<str> for $name {
+            #[inline]
+            fn as_ref(&self) -> &str {
+                self.0.as_str()
+            }
+        }
+
+        impl ::core::convert::AsRef<[u8]> for $name {
+            #[inline]
+            fn as_ref(&self) -> &[u8] {
+                self.0.as_bytes()
+            }
+        }
+
+        impl ::core::convert::AsRef<$crate::ArcStr> for $name {
+            #[inline]
+            fn as_ref(&self) -> &$crate::ArcStr {
+                &self.0
+            }
+        }
+
+        impl ::core::borrow::Borrow<str> for $name {
+            #[inline]
+            fn borrow(&self) -> &str {
+                self.0.as_str()
+            }
+        }
+
+        impl ::core::fmt::Debug for $name {
+            #[inline]
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                ::core::fmt::Debug::fmt(&self.0, f)
+            }
+        }
+
+        impl ::core::fmt::Display for $name {
+            #[inline]
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                ::core::fmt::Display::fmt(&self.0, f)
+            }
+        }
+
+        impl ::core::cmp::PartialEq<str> for $name {
+            #[inline]
+            fn eq(&self, o: &str) -> bool {
+                self.0.as_str() == o
+            }
+        }
+
+        impl<'a> ::core::cmp::PartialEq<&'a str> for $name {
+            #[inline]
+            fn eq(&self, o: &&'a str) -> bool {
+                self.0.as_str() == *o
+            }
+        }
+
+        impl ::core::cmp::PartialEq<$crate::ArcStr> for $name {
+            #[inline]
+            fn eq(&self, o: &$crate::ArcStr) -> bool {
+                self.0 == *o
+            }
+        }
+
+        impl ::core::cmp::PartialEq<$name> for $crate::ArcStr {
+            #[inline]
+            fn eq(&self, o: &$name) -> bool {
+                *self == o.0
+            }
+        }
+
+        impl ::core::cmp::PartialEq<$name> for str {
+            #[inline]
+            fn eq(&self, o: &$name) -> bool {
+                self == o.0.as_str()
+            }
+        }
+
+        impl<'a> ::core::cmp::PartialEq<$name> for &'a str {
+            #[inline]
+            fn eq(&self, o: &$name) -> bool {
+                *self == o.0.as_str()
+            }
+        }
+
+        impl ::core::cmp::PartialEq<$crate::private_::String> for $name {
+            #[inline]
+            fn eq(&self, o: &$crate::private_::String) -> bool {
+                self.0.as_str() == o.as_str()
+            }
+        }
+
+        impl ::core::cmp::PartialEq<$name> for $crate::private_::String {
+            #[inline]
+            fn eq(&self, o: &$name) -> bool {
+                self.as_str() == o.0.as_str()
+            }
+        }
+
+        $crate::arcstr_newtype!(@index $name;
+            ::core::ops::RangeFull,
+            ::core::ops::Range<usize>,
+            ::core::ops::RangeFrom<usize>,
+            ::core::ops::RangeTo<usize>,
+            ::core::ops::RangeInclusive<usize>,
+            ::core::ops::RangeToInclusive<usize>,
+        );
+
+        impl ::core::convert::From<$name> for $crate::ArcStr {
+            #[inline]
+            fn from(v: $name) -> $crate::ArcStr {
+                v.0
+            }
+        }
+
+        impl ::core::convert::TryFrom<$crate::ArcStr> for $name {
+            type Error = $err;
+            #[inline]
+            fn try_from(s: $crate::ArcStr) -> ::core::result::Result<Self, $err> {
+                Self::new(s)
+            }
+        }
+
+        impl<'a> ::core::convert::TryFrom<&'a str> for $name {
+            type Error = $err;
+            #[inline]
+            fn try_from(s: &'a str) -> ::core::result::Result<Self, $err> {
+                // Validate before allocating.
+                $validate(s)?;
+                ::core::result::Result::Ok(Self($crate::ArcStr::from(s)))
+            }
+        }
+
+        impl ::core::convert::TryFrom<$crate::private_::String> for $name {
+            type Error = $err;
+            #[inline]
+            fn try_from(s: $crate::private_::String) -> ::core::result::Result<Self, $err> {
+                <Self as ::core::convert::TryFrom<&str>>::try_from(s.as_str())
+            }
+        }
+
+        impl ::core::str::FromStr for $name {
+            type Err = $err;
+            #[inline]
+            fn from_str(s: &str) -> ::core::result::Result<Self, $err> {
+                <Self as ::core::convert::TryFrom<&str>>::try_from(s)
+            }
+        }
+    };
+    (@index $name:ident; $($idx:ty,)*) => {$(
+        impl ::core::ops::Index<$idx> for $name {
+            type Output = str;
+            #[inline]
+            fn index(&self, i: $idx) -> &str {
+                &self.0.as_str()[i]
+            }
+        }
+    )*};
+}
+
+// Not public API. The serde impls for `arcstr_newtype!` need our `serde`
+// feature, which the caller can't check for, so they're generated by a macro
+// which is defined differently depending on whether it's enabled.
+#[cfg(feature = "serde")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __arcstr_newtype_serde {
+    ($name:ident) => {
+        impl $crate::private_::serde::Serialize for $name {
+            #[inline]
+            fn serialize<S: $crate::private_::serde::Serializer>(
+                &self,
+                ser: S,
+            ) -> ::core::result::Result<S::Ok, S::Error> {
+                $crate::private_::serde::Serialize::serialize(&self.0, ser)
+            }
+        }
+
+        impl<'de> $crate::private_::serde::Deserialize<'de> for $name {
+            fn deserialize<D: $crate::private_::serde::Deserializer<'de>>(
+                d: D,
+            ) -> ::core::result::Result<Self, D::Error> {
+                use $crate::private_::{NewtypeErrorDisplay as _, NewtypeErrorFallback as _};
+                let s: $crate::ArcStr = $crate::private_::serde::Deserialize::deserialize(d)?;
+                Self::new(s).map_err(|e| {
+                    let msg = $crate::private_::NewtypeError(
+                        &e,
+                        ::core::concat!("invalid `", ::core::stringify!($name), "`"),
+                    );
+                    <D::Error as $crate::private_::serde::de::Error>::custom((&msg).as_display())
+                })
+            }
+        }
+    };
+}
+
+#[cfg(not(feature = "serde"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __arcstr_newtype_serde {
+    ($name:ident) => {
+        ::core::compile_error!(
+            "the `serde` option of `arcstr_newtype!` requires arcstr's `serde` feature"
+        );
+    };
+}
+
+// Not public API. Used by `__arcstr_newtype_serde!` to get an error message
+// from the `Display` impl of the newtype's error, if it has one, without
+// requiring it. When called on a `&NewtypeError`, method resolution tries
+// `NewtypeErrorDisplay` (whose receiver is `&NewtypeError`) before
+// `NewtypeErrorFallback` (whose receiver is `&&NewtypeError`), but only if its
+// bound holds.
+#[doc(hidden)]
+pub struct NewtypeError<'a, T>(pub &'a T, pub &'static str);
+
+#[doc(hidden)]
+pub trait NewtypeErrorDisplay {
+    fn as_display(&self) -> &dyn core::fmt::Display;
+}
+
+impl<T: core::fmt::Display> NewtypeErrorDisplay for NewtypeError<'_, T> {
+    fn as_display(&self) -> &dyn core::fmt::Display {
+        self.0
+    }
+}
+
+#[doc(hidden)]
+pub trait NewtypeErrorFallback {
+    fn as_display(&self) -> &dyn core::fmt::Display;
+}
+
+impl<T> NewtypeErrorFallback for &NewtypeError<'_, T> {
+    fn as_display(&self) -> &dyn core::fmt::Display {
+        &self.1
+    }
+}
@@ -0,0 +1,47 @@
+use crate::ArcStr;
+
+/// The error returned when parsing an enum which derives
+/// [`ArcStrEnum`](crate::ArcStrEnum) from a string that isn't the name of one
+/// of its variants.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseVariantError {
+    enum_name: &'static str,
+    input: ArcStr,
+}
+
+impl ParseVariantError {
+    /// Returns the name of the enum we tried to parse.
+    #[inline]
+    pub fn enum_name(&self) -> &'static str {
+        self.enum_name
+    }
+
+    /// Returns the string which failed to parse.
+    #[inline]
+    pub fn input(&self) -> &ArcStr {
+        &self.input
+    }
+}
+
+impl core::fmt::Display for ParseVariantError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "unknown variant of `{}`: {:?}",
+            self.enum_name, self.input
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseVariantError {}
+
+// Not public API, exists for `#[derive(ArcStrEnum)]`.
+#[doc(hidden)]
+#[cold]
+pub fn parse_variant_error(enum_name: &'static str, input: &str) -> ParseVariantError {
+    ParseVariantError {
+        enum_name,
+        input: ArcStr::from(input),
+    }
+}
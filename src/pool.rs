@@ -0,0 +1,182 @@
+//! Thread-local caches of freed `ArcStr` allocations.
+//!
+//! When the `pool` feature is enabled, the allocations behind short
+//! heap-allocated [`ArcStr`](crate::ArcStr)s are rounded up to one of a few
+//! size classes, and instead of being returned to the global allocator when
+//! the last reference is dropped, they're kept in a small per-thread cache so
+//! that the next string of the same class can reuse them. This helps programs
+//! which build and drop very large numbers of short strings.
+//!
+//! Each thread's cache holds at most a fixed number of allocations per size
+//! class, and anything past that goes straight back to the global allocator.
+//! A thread's cache is emptied when the thread exits, or when it calls
+//! [`trim`].
+//!
+//! Strings may be dropped on a different thread from the one that created
+//! them. In that case the allocation ends up in the cache of the thread that
+//! dropped it, which is fine, since every cached allocation came from (and is
+//! eventually returned to) the global allocator.
+//!
+//! Only strings which store their data inline are pooled, so strings created
+//! by [`ArcStr::from_owner`](crate::ArcStr::from_owner) or
+//! [`ArcStr::from_str_padded`](crate::ArcStr::from_str_padded) are not
+//! affected.
+use core::alloc::Layout;
+use core::cell::Cell;
+use core::ptr;
+
+// Allocations are rounded up to a multiple of this...
+const GRANULE: usize = 16;
+// ...if they're at most this many bytes (including the header).
+const MAX_POOLED_SIZE: usize = 128;
+const NUM_CLASSES: usize = MAX_POOLED_SIZE / GRANULE;
+// The most allocations each thread caches per size class.
+const CLASS_CAPACITY: usize = 64;
+// The alignment of every pooled block, which is that of `ThinInner`.
+const ALIGN: usize = 8;
+
+/// Free all of the allocations cached by the current thread.
+///
+/// Other threads' caches are unaffected, and are freed when those threads
+/// call `trim` themselves, or exit.
+///
+/// # Examples
+///
+/// ```
+/// # use arcstr::ArcStr;
+/// drop(ArcStr::from("short"));
+/// assert!(arcstr::pool::cached_bytes() > 0);
+/// arcstr::pool::trim();
+/// assert_eq!(arcstr::pool::cached_bytes(), 0);
+/// ```
+pub fn trim() {
+    // If the thread is exiting, the cache is (or will be) freed anyway.
+    let _ = POOL.try_with(Pool::trim);
+}
+
+/// Returns the total size of the allocations cached by the current thread,
+/// in bytes.
+pub fn cached_bytes() -> usize {
+    POOL.try_with(|p| {
+        (0..NUM_CLASSES)
+            .map(|c| p.counts[c].get() * class_size(c))
+            .sum()
+    })
+    .unwrap_or(0)
+}
+
+// Returns the size that an allocation of `size` bytes should really use, so
+// that it's a valid block for its size class. Every allocation we hand to
+// `alloc`/`dealloc`/`realloc` must have its size computed by this, so that
+// blocks from the cache and from the global allocator are interchangeable.
+#[inline]
+pub(crate) const fn block_size(size: usize) -> usize {
+    if size <= MAX_POOLED_SIZE {
+        (size + GRANULE - 1) & !(GRANULE - 1)
+    } else {
+        size
+    }
+}
+
+#[inline]
+const fn class_size(class: usize) -> usize {
+    (class + 1) * GRANULE
+}
+
+#[inline]
+fn class_of(layout: Layout) -> Option<usize> {
+    let size = layout.size();
+    if size == 0 || size > MAX_POOLED_SIZE || layout.align() != ALIGN {
+        return None;
+    }
+    debug_assert_eq!(size, block_size(size));
+    Some(size / GRANULE - 1)
+}
+
+#[inline]
+pub(crate) unsafe fn alloc(layout: Layout) -> *mut u8 {
+    if let Some(class) = class_of(layout) {
+        if let Ok(Some(p)) = POOL.try_with(|pool| pool.pop(class)) {
+            return p;
+        }
+    }
+    alloc::alloc::alloc(layout)
+}
+
+#[inline]
+pub(crate) unsafe fn dealloc(p: *mut u8, layout: Layout) {
+    if let Some(class) = class_of(layout) {
+        if let Ok(true) = POOL.try_with(|pool| pool.push(class, p)) {
+            return;
+        }
+    }
+    alloc::alloc::dealloc(p, layout)
+}
+
+// A free list for each size class. Cached blocks are linked together through
+// their first word. This only uses `Cell`s, since nothing here can re-enter
+// the pool while it's being updated.
+struct Pool {
+    heads: [Cell<*mut u8>; NUM_CLASSES],
+    counts: [Cell<usize>; NUM_CLASSES],
+}
+
+std::thread_local! {
+    static POOL: Pool = const { Pool::new() };
+}
+
+impl Pool {
+    const fn new() -> Self {
+        #[allow(clippy::declare_interior_mutable_const)]
+        const NULL: Cell<*mut u8> = Cell::new(ptr::null_mut());
+        #[allow(clippy::declare_interior_mutable_const)]
+        const ZERO: Cell<usize> = Cell::new(0);
+        Self {
+            heads: [NULL; NUM_CLASSES],
+            counts: [ZERO; NUM_CLASSES],
+        }
+    }
+
+    #[inline]
+    fn pop(&self, class: usize) -> Option<*mut u8> {
+        let head = self.heads[class].get();
+        if head.is_null() {
+            return None;
+        }
+        // Safety: `head` is a cached block, which holds the next link.
+        self.heads[class].set(unsafe { *(head as *mut *mut u8) });
+        self.counts[class].set(self.counts[class].get() - 1);
+        Some(head)
+    }
+
+    // Returns false if the class is full, in which case the caller should
+    // free `p` itself.
+    #[inline]
+    unsafe fn push(&self, class: usize, p: *mut u8) -> bool {
+        let count = self.counts[class].get();
+        if count >= CLASS_CAPACITY {
+            return false;
+        }
+        *(p as *mut *mut u8) = self.heads[class].get();
+        self.heads[class].set(p);
+        self.counts[class].set(count + 1);
+        true
+    }
+
+    fn trim(&self) {
+        for class in 0..NUM_CLASSES {
+            // Safety: every block in this class came from the global
+            // allocator with this layout.
+            let layout = unsafe { Layout::from_size_align_unchecked(class_size(class), ALIGN) };
+            while let Some(p) = self.pop(class) {
+                unsafe { alloc::alloc::dealloc(p, layout) };
+            }
+        }
+    }
+}
+
+impl Drop for Pool {
+    fn drop(&mut self) {
+        self.trim();
+    }
+}
@@ -0,0 +1,20 @@
+//! Extra serde support for [`ArcStr`](crate::ArcStr).
+//!
+//! The `Serialize` and `Deserialize` impls on `ArcStr` itself treat it as an
+//! ordinary string. The things in here are opt-in alternatives, for when that
+//! isn't what you want.
+//!
+//! - [`shared`] (requires the `std` feature): Serializes a value along with a
+//!   table of the strings it contains, so that `ArcStr`s which were shared
+//!   before serialization are written once, and can be shared again after
+//!   deserialization (using the helpers in [`interning`]).
+//!
+//! - [`interning`]: Deserializes strings by looking them up in a table of
+//!   existing `ArcStr`s, so that repeated values share an allocation.
+
+pub mod interning;
+#[cfg(feature = "std")]
+pub mod shared;
+pub use interning::{InternTable, Interning};
+#[cfg(feature = "std")]
+pub use shared::Shared;
@@ -0,0 +1,321 @@
+//! Deserialization which reuses existing `ArcStr`s for repeated content.
+//!
+//! Deserializing an `ArcStr` normally allocates a new one every time. When the
+//! same values show up over and over (log fields, enum-like strings, map keys,
+//! etc), it's often better to look each string up in a table of ones you
+//! already have, and hand back a clone of the existing `ArcStr` instead.
+//!
+//! There are two ways to do this:
+//!
+//! - [`Interning`], a [`DeserializeSeed`] which interns into the table you give
+//!   it. This is the most direct option, but requires deserializing "by hand"
+//!   with a seed.
+//!
+//! - (Requires the `std` feature) The `deserialize_*` functions in this module,
+//!   which can be used with `#[serde(deserialize_with = "...")]`. These intern
+//!   into whichever table was passed to the innermost enclosing call to
+//!   [`with_table`] on the current thread. Outside of `with_table`, they behave
+//!   the same as deserializing normally. See [`with_table`] for an example.
+//!
+//!   These are also how strings inside a value deserialized with
+//!   [`shared`](super::shared) get shared, since the value's string table is
+//!   the active table while it's deserialized.
+//!
+//! [`DeserializeSeed`]: ::serde::de::DeserializeSeed
+
+use crate::ArcStr;
+use ::serde::de::{self, DeserializeSeed, Deserializer, Visitor};
+use alloc::collections::BTreeSet;
+use core::fmt;
+
+/// A table of strings, which [`Interning`] uses to find an existing `ArcStr`
+/// with the same contents as a deserialized string.
+///
+/// This is implemented for `BTreeSet<ArcStr>`, and with the `std` feature,
+/// `HashSet<ArcStr, S>`. Note that these grow without bound, so you may want
+/// to clear them (or start over with a new one) from time to time.
+pub trait InternTable {
+    /// Return an `ArcStr` equal to `s`, reusing one from the table if possible,
+    /// and otherwise allocating one (and usually adding it to the table).
+    fn intern(&mut self, s: &str) -> ArcStr;
+}
+
+impl InternTable for BTreeSet<ArcStr> {
+    fn intern(&mut self, s: &str) -> ArcStr {
+        if let Some(existing) = self.get(s) {
+            return existing.clone();
+        }
+        let new = ArcStr::from(s);
+        self.insert(new.clone());
+        new
+    }
+}
+
+#[cfg(feature = "std")]
+impl<S: core::hash::BuildHasher> InternTable for std::collections::HashSet<ArcStr, S> {
+    fn intern(&mut self, s: &str) -> ArcStr {
+        if let Some(existing) = self.get(s) {
+            return existing.clone();
+        }
+        let new = ArcStr::from(s);
+        self.insert(new.clone());
+        new
+    }
+}
+
+/// A [`DeserializeSeed`] which deserializes a string into an `ArcStr`, reusing
+/// one from an [`InternTable`] when possible.
+///
+/// # Example
+///
+/// ```
+/// # use arcstr::ArcStr;
+/// use arcstr::serde::Interning;
+/// use serde::de::DeserializeSeed;
+/// use std::collections::BTreeSet;
+///
+/// let mut table = BTreeSet::new();
+/// let mut de = serde_json::Deserializer::from_str(r#""hello""#);
+/// let a = Interning::new(&mut table).deserialize(&mut de).unwrap();
+/// let mut de = serde_json::Deserializer::from_str(r#""hello""#);
+/// let b = Interning::new(&mut table).deserialize(&mut de).unwrap();
+/// assert!(ArcStr::ptr_eq(&a, &b));
+/// ```
+///
+/// [`DeserializeSeed`]: ::serde::de::DeserializeSeed
+pub struct Interning<'a> {
+    table: &'a mut dyn InternTable,
+}
+
+impl<'a> Interning<'a> {
+    /// Create a seed which interns into `table`.
+    #[inline]
+    pub fn new(table: &'a mut dyn InternTable) -> Self {
+        Self { table }
+    }
+}
+
+impl<'de> DeserializeSeed<'de> for Interning<'_> {
+    type Value = ArcStr;
+    fn deserialize<D: Deserializer<'de>>(self, d: D) -> Result<ArcStr, D::Error> {
+        d.deserialize_str(self)
+    }
+}
+
+impl<'de> Visitor<'de> for Interning<'_> {
+    type Value = ArcStr;
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("a string")
+    }
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<ArcStr, E> {
+        Ok(self.table.intern(v))
+    }
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<ArcStr, E> {
+        match core::str::from_utf8(v) {
+            Ok(s) => Ok(self.table.intern(s)),
+            Err(_) => Err(de::Error::invalid_value(de::Unexpected::Bytes(v), &self)),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+pub use self::scoped::*;
+
+#[cfg(feature = "std")]
+mod scoped {
+    use super::{InternTable, Interning};
+    use crate::ArcStr;
+    use ::serde::de::{DeserializeSeed, Deserializer, MapAccess, SeqAccess, Visitor};
+    use ::serde::Deserialize;
+    use alloc::vec::Vec;
+    use core::cell::Cell;
+    use core::fmt;
+    use core::marker::PhantomData;
+
+    type TablePtr = *mut (dyn InternTable + 'static);
+
+    std::thread_local! {
+        static ACTIVE: Cell<Option<TablePtr>> = const { Cell::new(None) };
+    }
+
+    /// Restores the previously active table when dropped.
+    struct Restore(Option<TablePtr>);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            ACTIVE.with(|a| a.set(self.0));
+        }
+    }
+
+    /// Run `f` with `table` as the active intern table for the `deserialize_*`
+    /// functions in this module, on the current thread.
+    ///
+    /// Calls may be nested, in which case the innermost table is used until
+    /// its `with_table` returns.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use arcstr::ArcStr;
+    /// # use serde::Deserialize;
+    /// use arcstr::serde::interning;
+    /// use std::collections::HashSet;
+    ///
+    /// #[derive(Deserialize)]
+    /// struct LogLine {
+    ///     #[serde(deserialize_with = "interning::deserialize")]
+    ///     level: ArcStr,
+    ///     #[serde(deserialize_with = "interning::deserialize_option")]
+    ///     target: Option<ArcStr>,
+    ///     message: String,
+    /// }
+    ///
+    /// let input = r#"[
+    ///     {"level": "INFO", "target": "net", "message": "connecting"},
+    ///     {"level": "INFO", "target": "net", "message": "connected"}
+    /// ]"#;
+    /// let mut table = HashSet::new();
+    /// let lines: Vec<LogLine> =
+    ///     interning::with_table(&mut table, || serde_json::from_str(input)).unwrap();
+    ///
+    /// assert!(ArcStr::ptr_eq(&lines[0].level, &lines[1].level));
+    /// assert!(ArcStr::ptr_eq(
+    ///     lines[0].target.as_ref().unwrap(),
+    ///     lines[1].target.as_ref().unwrap(),
+    /// ));
+    /// assert_eq!(table.len(), 2);
+    /// ```
+    pub fn with_table<R>(table: &mut dyn InternTable, f: impl FnOnce() -> R) -> R {
+        // Safety: We only hand out the pointer (briefly) while `table` is
+        // still mutably borrowed by us, and `Restore` removes it again before
+        // we return, even if `f` panics.
+        let ptr: TablePtr =
+            unsafe { core::mem::transmute::<*mut (dyn InternTable + '_), TablePtr>(table) };
+        let _restore = Restore(ACTIVE.with(|a| a.replace(Some(ptr))));
+        f()
+    }
+
+    fn intern_with_active<'de, D: Deserializer<'de>>(d: D) -> Result<ArcStr, D::Error> {
+        // Take the table out while we use it, so that if we somehow get
+        // called again before returning (say, by the `InternTable` impl), we
+        // can't end up with two `&mut`s to it.
+        let active = ACTIVE.with(Cell::take);
+        let _restore = Restore(active);
+        match active {
+            // Safety: See `with_table`.
+            Some(ptr) => Interning::new(unsafe { &mut *ptr }).deserialize(d),
+            None => ArcStr::deserialize(d),
+        }
+    }
+
+    /// Wraps an `ArcStr` so that it's interned when used as a `Deserialize`
+    /// type parameter (`Option<Interned>`, etc).
+    struct Interned(ArcStr);
+
+    impl<'de> Deserialize<'de> for Interned {
+        #[inline]
+        fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+            intern_with_active(d).map(Interned)
+        }
+    }
+
+    /// Deserialize an `ArcStr`, interning it into the active table.
+    ///
+    /// For use with `#[serde(deserialize_with = "...")]`. See the [module
+    /// documentation](super) for details.
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<ArcStr, D::Error> {
+        intern_with_active(d)
+    }
+
+    /// Deserialize an `Option<ArcStr>`, interning it into the active table.
+    ///
+    /// For use with `#[serde(deserialize_with = "...")]`. See the [module
+    /// documentation](super) for details.
+    pub fn deserialize_option<'de, D: Deserializer<'de>>(d: D) -> Result<Option<ArcStr>, D::Error> {
+        Ok(Option::<Interned>::deserialize(d)?.map(|Interned(s)| s))
+    }
+
+    /// Deserialize a `Vec<ArcStr>`, interning each element into the active
+    /// table.
+    ///
+    /// For use with `#[serde(deserialize_with = "...")]`. See the [module
+    /// documentation](super) for details.
+    pub fn deserialize_vec<'de, D: Deserializer<'de>>(d: D) -> Result<Vec<ArcStr>, D::Error> {
+        d.deserialize_seq(VecVisitor)
+    }
+
+    struct VecVisitor;
+
+    impl<'de> Visitor<'de> for VecVisitor {
+        type Value = Vec<ArcStr>;
+        fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str("a sequence of strings")
+        }
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+            // Don't trust the size hint too much.
+            let mut v = Vec::with_capacity(seq.size_hint().unwrap_or(0).min(4096));
+            while let Some(Interned(s)) = seq.next_element()? {
+                v.push(s);
+            }
+            Ok(v)
+        }
+    }
+
+    /// Deserialize a map with `ArcStr` keys (such as `HashMap<ArcStr, V>` or
+    /// `BTreeMap<ArcStr, V>`), interning each key into the active table.
+    ///
+    /// The values are deserialized normally.
+    ///
+    /// For use with `#[serde(deserialize_with = "...")]`. See the [module
+    /// documentation](super) for details.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use arcstr::ArcStr;
+    /// # use serde::Deserialize;
+    /// use arcstr::serde::interning;
+    /// use std::collections::{BTreeSet, HashMap};
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Counts(
+    ///     #[serde(deserialize_with = "interning::deserialize_map_keys")]
+    ///     HashMap<ArcStr, u32>,
+    /// );
+    ///
+    /// let mut table = BTreeSet::new();
+    /// let counts: Counts = interning::with_table(&mut table, || {
+    ///     serde_json::from_str(r#"{"a": 1, "b": 2}"#)
+    /// }).unwrap();
+    /// assert_eq!(counts.0["a"], 1);
+    /// assert!(table.contains("b"));
+    /// ```
+    pub fn deserialize_map_keys<'de, D, M, V>(d: D) -> Result<M, D::Error>
+    where
+        D: Deserializer<'de>,
+        M: Default + Extend<(ArcStr, V)>,
+        V: Deserialize<'de>,
+    {
+        d.deserialize_map(MapVisitor(PhantomData))
+    }
+
+    struct MapVisitor<M, V>(PhantomData<(M, V)>);
+
+    impl<'de, M, V> Visitor<'de> for MapVisitor<M, V>
+    where
+        M: Default + Extend<(ArcStr, V)>,
+        V: Deserialize<'de>,
+    {
+        type Value = M;
+        fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str("a map with string keys")
+        }
+        fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<M, A::Error> {
+            let mut m = M::default();
+            while let Some((Interned(k), v)) = map.next_entry()? {
+                m.extend(core::iter::once((k, v)));
+            }
+            Ok(m)
+        }
+    }
+}
@@ -0,0 +1,995 @@
+//! Serialization which preserves sharing between `ArcStr`s.
+//!
+//! Normally, an `ArcStr` is serialized as a plain string. This means that if a
+//! value holds 10,000 clones of the same `ArcStr`, all 10,000 copies get
+//! written out, and deserializing it back produces 10,000 separate allocations.
+//!
+//! The functions in this module avoid that. They serialize a value as a pair of
+//! `(table, value)`, where `table` is a list of the distinct strings inside the
+//! value, and each string inside `value` is replaced by its (`u32`) index in
+//! the table.
+//!
+//! When deserializing, one `ArcStr` is created per table entry. The table is
+//! the active table for the helpers in [`interning`] while the value is
+//! deserialized, so every string which is deserialized with one of them (such
+//! as `#[serde(deserialize_with = "arcstr::serde::interning::deserialize")]`)
+//! comes back as a clone of its entry. `ArcStr::ptr_eq` holds between those
+//! strings exactly when it did before serialization. Strings that aren't
+//! deserialized with those helpers are deserialized as usual, and so come back
+//! equal, but not shared.
+//!
+//! Two `ArcStr`s get the same table entry if they're clones of each other (that
+//! is, if they're [`ArcStr::ptr_eq`]), not merely because they're equal. Other
+//! strings (`String`s, `&str`s, and so on) are written to the table too, and
+//! likewise only share an entry with a string at the same address.
+//!
+//! This works with any serde format and any value which contains `ArcStr`s,
+//! however deeply nested. It can be used either via `#[serde(with = "...")]`,
+//! or by wrapping the value in [`Shared`].
+//!
+//! # Example
+//!
+//! ```
+//! # use arcstr::ArcStr;
+//! # use serde::{Serialize, Deserialize};
+//! use arcstr::serde::interning;
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Document {
+//!     #[serde(deserialize_with = "interning::deserialize_vec")]
+//!     words: Vec<ArcStr>,
+//! }
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Library {
+//!     #[serde(with = "arcstr::serde::shared")]
+//!     doc: Document,
+//! }
+//!
+//! let word = ArcStr::from("buffalo");
+//! let lib = Library { doc: Document { words: vec![word.clone(); 8] } };
+//! let json = serde_json::to_string(&lib).unwrap();
+//! assert_eq!(json, r#"{"doc":[["buffalo"],{"words":[0,0,0,0,0,0,0,0]}]}"#);
+//!
+//! let lib: Library = serde_json::from_str(&json).unwrap();
+//! assert!(ArcStr::ptr_eq(&lib.doc.words[0], &lib.doc.words[7]));
+//! ```
+//!
+//! # Notes
+//!
+//! This works by wrapping the `Serializer` (or `Deserializer`) in one which
+//! swaps every string for its index, so the `Serialize` and `Deserialize` impls
+//! of `ArcStr` itself are the usual ones, and aren't affected by any of this.
+//!
+//! Since every string is written as an index, types whose `Deserialize` impl
+//! goes through `deserialize_any` and expects to see a string (such as
+//! `#[serde(untagged)]` and internally tagged enums) don't work inside a shared
+//! value.
+//!
+//! Serializing a value traverses it twice: once to build the table, and once
+//! to write it out, so its `Serialize` impl must behave the same way both
+//! times.
+//!
+//! Nesting is allowed, in which case the inner value gets a table of its own.
+//!
+//! [`ArcStr::ptr_eq`]: crate::ArcStr::ptr_eq
+//! [`interning`]: super::interning
+
+use super::interning::{self, InternTable};
+use crate::ArcStr;
+use ::serde::de::{self, Deserialize, DeserializeSeed, Deserializer, SeqAccess, Visitor};
+use ::serde::ser::{self, Serialize, SerializeTuple, Serializer};
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::cell::RefCell;
+use core::convert::TryFrom;
+use core::fmt;
+use core::marker::PhantomData;
+use std::collections::HashMap;
+
+/// The strings collected from a value while serializing it.
+#[derive(Default)]
+struct Strings {
+    table: Vec<ArcStr>,
+    // Keyed on the address and length of the string, so that clones of an
+    // `ArcStr` share an entry, but equal `ArcStr`s don't.
+    by_ptr: HashMap<(usize, usize), u32>,
+    // The first entry for each distinct string. Used for strings that don't
+    // live in the same place both times we see them (for example ones which
+    // are formatted into a temporary buffer).
+    by_value: HashMap<ArcStr, u32>,
+}
+
+impl Strings {
+    fn insert(&mut self, s: &str) -> Result<(), &'static str> {
+        let key = (s.as_ptr() as usize, s.len());
+        if let Some(&i) = self.by_ptr.get(&key) {
+            if self.table[i as usize] == s {
+                return Ok(());
+            }
+        }
+        let i =
+            u32::try_from(self.table.len()).map_err(|_| "too many strings for the string table")?;
+        let s = ArcStr::from(s);
+        self.by_value.entry(s.clone()).or_insert(i);
+        self.by_ptr.insert(key, i);
+        self.table.push(s);
+        Ok(())
+    }
+
+    fn index_of(&self, s: &str) -> Result<u32, &'static str> {
+        if let Some(&i) = self.by_ptr.get(&(s.as_ptr() as usize, s.len())) {
+            if self.table[i as usize] == s {
+                return Ok(i);
+            }
+        }
+        self.by_value.get(s).copied().ok_or(
+            "string was not present in the string table (the value's `Serialize` impl is not deterministic)",
+        )
+    }
+}
+
+/// Serialize `value`, writing each distinct `ArcStr` it contains only once.
+///
+/// See the [module documentation](self) for details.
+pub fn serialize<T, S>(value: &T, ser: S) -> Result<S::Ok, S::Error>
+where
+    T: ?Sized + Serialize,
+    S: Serializer,
+{
+    let strings = RefCell::new(Strings::default());
+    value
+        .serialize(Collector {
+            strings: &strings,
+            human_readable: ser.is_human_readable(),
+        })
+        .map_err(|CollectError(e)| ser::Error::custom(e))?;
+    let strings = strings.into_inner();
+    let mut tup = ser.serialize_tuple(2)?;
+    tup.serialize_element(&Table(&strings.table))?;
+    tup.serialize_element(&Indexed {
+        inner: value,
+        strings: &strings,
+    })?;
+    tup.end()
+}
+
+/// Deserialize a value written by [`serialize`], sharing a single allocation
+/// between every `ArcStr` that refers to the same table entry (and is
+/// deserialized with one of the [`interning`] helpers).
+///
+/// See the [module documentation](self) for details.
+pub fn deserialize<'de, T, D>(d: D) -> Result<T, D::Error>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    d.deserialize_tuple(2, SharedVisitor(PhantomData))
+}
+
+/// A wrapper which serializes its contents using [`serialize`] and
+/// [`deserialize`].
+///
+/// This is useful when you can't use `#[serde(with = "...")]`, for example
+/// when the value is the root of the document.
+///
+/// # Example
+///
+/// ```
+/// # use arcstr::ArcStr;
+/// # use serde::{Serialize, Deserialize};
+/// use arcstr::serde::{interning, Shared};
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct Tags(#[serde(deserialize_with = "interning::deserialize_vec")] Vec<ArcStr>);
+///
+/// let a = ArcStr::from("a");
+/// let v = Shared(Tags(vec![a.clone(), a.clone(), ArcStr::from("b")]));
+/// let json = serde_json::to_string(&v).unwrap();
+/// assert_eq!(json, r#"[["a","b"],[0,0,1]]"#);
+///
+/// let Shared(Tags(v)) = serde_json::from_str(&json).unwrap();
+/// assert!(ArcStr::ptr_eq(&v[0], &v[1]));
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Shared<T>(pub T);
+
+impl<T: Serialize> Serialize for Shared<T> {
+    #[inline]
+    fn serialize<S: Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+        serialize(&self.0, ser)
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Shared<T> {
+    #[inline]
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        deserialize(d).map(Shared)
+    }
+}
+
+/// The string table, which is always written as plain strings.
+struct Table<'a>(&'a [ArcStr]);
+
+impl Serialize for Table<'_> {
+    fn serialize<S: Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+        ser.collect_seq(self.0.iter().map(ArcStr::as_str))
+    }
+}
+
+/// The table, as the active [`InternTable`] while the value is deserialized.
+///
+/// Strings are looked up by address, since the ones from the table are handed
+/// to visitors straight from its entries. This means that equal entries stay
+/// distinct.
+struct Lookup<'t> {
+    table: &'t [ArcStr],
+    by_ptr: HashMap<usize, usize>,
+}
+
+impl<'t> Lookup<'t> {
+    fn new(table: &'t [ArcStr]) -> Self {
+        let by_ptr = table
+            .iter()
+            .enumerate()
+            .map(|(i, s)| (s.as_ptr() as usize, i))
+            .collect();
+        Self { table, by_ptr }
+    }
+}
+
+impl InternTable for Lookup<'_> {
+    fn intern(&mut self, s: &str) -> ArcStr {
+        match self.by_ptr.get(&(s.as_ptr() as usize)) {
+            Some(&i) if self.table[i].len() == s.len() => self.table[i].clone(),
+            // Not from the table, so there's nothing to share with.
+            _ => ArcStr::from(s),
+        }
+    }
+}
+
+struct SharedVisitor<T>(PhantomData<T>);
+
+impl<'de, T: Deserialize<'de>> Visitor<'de> for SharedVisitor<T> {
+    type Value = T;
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a string table followed by a value")
+    }
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<T, A::Error> {
+        let table: Vec<ArcStr> = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+        let mut lookup = Lookup::new(&table);
+        interning::with_table(&mut lookup, || {
+            seq.next_element_seed(FromTable {
+                inner: PhantomData::<T>,
+                table: &table,
+            })
+        })?
+        .ok_or_else(|| de::Error::invalid_length(1, &self))
+    }
+}
+
+/// Wraps a value or a `Serializer` (or one of its helpers), replacing every
+/// string that passes through it with its index in `strings`.
+struct Indexed<'a, T> {
+    inner: T,
+    strings: &'a Strings,
+}
+
+impl<'a, T> Indexed<'a, T> {
+    #[inline]
+    fn wrap<U>(&self, inner: U) -> Indexed<'a, U> {
+        Indexed {
+            inner,
+            strings: self.strings,
+        }
+    }
+}
+
+impl<T: ?Sized + Serialize> Serialize for Indexed<'_, &T> {
+    #[inline]
+    fn serialize<S: Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+        self.inner.serialize(self.wrap(ser))
+    }
+}
+
+macro_rules! forward_serialize {
+    ($($method:ident($ty:ty);)*) => {$(
+        #[inline]
+        fn $method(self, v: $ty) -> Result<S::Ok, S::Error> {
+            self.inner.$method(v)
+        }
+    )*};
+}
+
+impl<'a, S: Serializer> Serializer for Indexed<'a, S> {
+    type Ok = S::Ok;
+    type Error = S::Error;
+    type SerializeSeq = Indexed<'a, S::SerializeSeq>;
+    type SerializeTuple = Indexed<'a, S::SerializeTuple>;
+    type SerializeTupleStruct = Indexed<'a, S::SerializeTupleStruct>;
+    type SerializeTupleVariant = Indexed<'a, S::SerializeTupleVariant>;
+    type SerializeMap = Indexed<'a, S::SerializeMap>;
+    type SerializeStruct = Indexed<'a, S::SerializeStruct>;
+    type SerializeStructVariant = Indexed<'a, S::SerializeStructVariant>;
+
+    forward_serialize! {
+        serialize_bool(bool);
+        serialize_i8(i8);
+        serialize_i16(i16);
+        serialize_i32(i32);
+        serialize_i64(i64);
+        serialize_i128(i128);
+        serialize_u8(u8);
+        serialize_u16(u16);
+        serialize_u32(u32);
+        serialize_u64(u64);
+        serialize_u128(u128);
+        serialize_f32(f32);
+        serialize_f64(f64);
+        serialize_char(char);
+        serialize_bytes(&[u8]);
+        serialize_unit_struct(&'static str);
+    }
+
+    fn serialize_str(self, v: &str) -> Result<S::Ok, S::Error> {
+        let index = self.strings.index_of(v).map_err(ser::Error::custom)?;
+        self.inner.serialize_u32(index)
+    }
+    fn collect_str<T: ?Sized + fmt::Display>(self, v: &T) -> Result<S::Ok, S::Error> {
+        self.serialize_str(&v.to_string())
+    }
+    fn serialize_none(self) -> Result<S::Ok, S::Error> {
+        self.inner.serialize_none()
+    }
+    fn serialize_some<T: ?Sized + Serialize>(self, v: &T) -> Result<S::Ok, S::Error> {
+        let v = self.wrap(v);
+        self.inner.serialize_some(&v)
+    }
+    fn serialize_unit(self) -> Result<S::Ok, S::Error> {
+        self.inner.serialize_unit()
+    }
+    fn serialize_unit_variant(
+        self,
+        name: &'static str,
+        index: u32,
+        variant: &'static str,
+    ) -> Result<S::Ok, S::Error> {
+        self.inner.serialize_unit_variant(name, index, variant)
+    }
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        name: &'static str,
+        v: &T,
+    ) -> Result<S::Ok, S::Error> {
+        let v = self.wrap(v);
+        self.inner.serialize_newtype_struct(name, &v)
+    }
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        name: &'static str,
+        index: u32,
+        variant: &'static str,
+        v: &T,
+    ) -> Result<S::Ok, S::Error> {
+        let v = self.wrap(v);
+        self.inner
+            .serialize_newtype_variant(name, index, variant, &v)
+    }
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, S::Error> {
+        let inner = self.inner.serialize_seq(len)?;
+        Ok(Indexed {
+            inner,
+            strings: self.strings,
+        })
+    }
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, S::Error> {
+        let inner = self.inner.serialize_tuple(len)?;
+        Ok(Indexed {
+            inner,
+            strings: self.strings,
+        })
+    }
+    fn serialize_tuple_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleStruct, S::Error> {
+        let inner = self.inner.serialize_tuple_struct(name, len)?;
+        Ok(Indexed {
+            inner,
+            strings: self.strings,
+        })
+    }
+    fn serialize_tuple_variant(
+        self,
+        name: &'static str,
+        index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleVariant, S::Error> {
+        let inner = self
+            .inner
+            .serialize_tuple_variant(name, index, variant, len)?;
+        Ok(Indexed {
+            inner,
+            strings: self.strings,
+        })
+    }
+    fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap, S::Error> {
+        let inner = self.inner.serialize_map(len)?;
+        Ok(Indexed {
+            inner,
+            strings: self.strings,
+        })
+    }
+    fn serialize_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStruct, S::Error> {
+        let inner = self.inner.serialize_struct(name, len)?;
+        Ok(Indexed {
+            inner,
+            strings: self.strings,
+        })
+    }
+    fn serialize_struct_variant(
+        self,
+        name: &'static str,
+        index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStructVariant, S::Error> {
+        let inner = self
+            .inner
+            .serialize_struct_variant(name, index, variant, len)?;
+        Ok(Indexed {
+            inner,
+            strings: self.strings,
+        })
+    }
+    fn is_human_readable(&self) -> bool {
+        self.inner.is_human_readable()
+    }
+}
+
+macro_rules! indexed_compound {
+    ($($tr:ident::$method:ident($($name:ident: $ty:ty),*);)*) => {$(
+        impl<S: ser::$tr> ser::$tr for Indexed<'_, S> {
+            type Ok = S::Ok;
+            type Error = S::Error;
+            #[inline]
+            fn $method<T: ?Sized + Serialize>(&mut self, $($name: $ty,)* v: &T) -> Result<(), S::Error> {
+                let v = self.wrap(v);
+                self.inner.$method($($name,)* &v)
+            }
+            #[inline]
+            fn end(self) -> Result<S::Ok, S::Error> {
+                self.inner.end()
+            }
+        }
+    )*};
+}
+
+indexed_compound! {
+    SerializeSeq::serialize_element();
+    SerializeTuple::serialize_element();
+    SerializeTupleStruct::serialize_field();
+    SerializeTupleVariant::serialize_field();
+    SerializeStruct::serialize_field(key: &'static str);
+    SerializeStructVariant::serialize_field(key: &'static str);
+}
+
+impl<S: ser::SerializeMap> ser::SerializeMap for Indexed<'_, S> {
+    type Ok = S::Ok;
+    type Error = S::Error;
+    #[inline]
+    fn serialize_key<T: ?Sized + Serialize>(&mut self, k: &T) -> Result<(), S::Error> {
+        let k = self.wrap(k);
+        self.inner.serialize_key(&k)
+    }
+    #[inline]
+    fn serialize_value<T: ?Sized + Serialize>(&mut self, v: &T) -> Result<(), S::Error> {
+        let v = self.wrap(v);
+        self.inner.serialize_value(&v)
+    }
+    #[inline]
+    fn end(self) -> Result<S::Ok, S::Error> {
+        self.inner.end()
+    }
+}
+
+/// Wraps a `Deserializer` (or one of its helpers, or a visitor or seed),
+/// replacing every index that passes through it with the string from `table`
+/// that it refers to.
+struct FromTable<'t, T> {
+    inner: T,
+    table: &'t [ArcStr],
+}
+
+impl<'t, T> FromTable<'t, T> {
+    #[inline]
+    fn wrap<U>(&self, inner: U) -> FromTable<'t, U> {
+        FromTable {
+            inner,
+            table: self.table,
+        }
+    }
+}
+
+impl<'de, T: DeserializeSeed<'de>> DeserializeSeed<'de> for FromTable<'_, T> {
+    type Value = T::Value;
+    #[inline]
+    fn deserialize<D: Deserializer<'de>>(self, d: D) -> Result<T::Value, D::Error> {
+        let d = self.wrap(d);
+        self.inner.deserialize(d)
+    }
+}
+
+macro_rules! forward_deserialize {
+    ($($method:ident($($name:ident: $ty:ty),*);)*) => {$(
+        #[inline]
+        fn $method<V: Visitor<'de>>(self, $($name: $ty,)* visitor: V) -> Result<V::Value, D::Error> {
+            let visitor = self.wrap(visitor);
+            self.inner.$method($($name,)* visitor)
+        }
+    )*};
+}
+
+impl<'de, D: Deserializer<'de>> Deserializer<'de> for FromTable<'_, D> {
+    type Error = D::Error;
+
+    forward_deserialize! {
+        deserialize_any();
+        deserialize_bool();
+        deserialize_i8();
+        deserialize_i16();
+        deserialize_i32();
+        deserialize_i64();
+        deserialize_i128();
+        deserialize_u8();
+        deserialize_u16();
+        deserialize_u32();
+        deserialize_u64();
+        deserialize_u128();
+        deserialize_f32();
+        deserialize_f64();
+        deserialize_char();
+        deserialize_bytes();
+        deserialize_byte_buf();
+        deserialize_option();
+        deserialize_unit();
+        deserialize_unit_struct(name: &'static str);
+        deserialize_newtype_struct(name: &'static str);
+        deserialize_seq();
+        deserialize_tuple(len: usize);
+        deserialize_tuple_struct(name: &'static str, len: usize);
+        deserialize_map();
+        deserialize_struct(name: &'static str, fields: &'static [&'static str]);
+        deserialize_enum(name: &'static str, variants: &'static [&'static str]);
+        deserialize_identifier();
+        deserialize_ignored_any();
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, D::Error> {
+        self.inner.deserialize_u32(Entry {
+            visitor,
+            table: self.table,
+        })
+    }
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, D::Error> {
+        self.deserialize_str(visitor)
+    }
+    fn is_human_readable(&self) -> bool {
+        self.inner.is_human_readable()
+    }
+}
+
+/// Reads an index, and passes the string it refers to on to `visitor`.
+struct Entry<'t, V> {
+    visitor: V,
+    table: &'t [ArcStr],
+}
+
+impl<'de, V: Visitor<'de>> Visitor<'de> for Entry<'_, V> {
+    type Value = V::Value;
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("an index into the string table")
+    }
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<V::Value, E> {
+        let s = usize::try_from(v)
+            .ok()
+            .and_then(|i| self.table.get(i))
+            .ok_or_else(|| de::Error::invalid_value(de::Unexpected::Unsigned(v), &self))?;
+        self.visitor.visit_str(s)
+    }
+}
+
+macro_rules! forward_visit {
+    ($($method:ident($ty:ty);)*) => {$(
+        #[inline]
+        fn $method<E: de::Error>(self, v: $ty) -> Result<V::Value, E> {
+            self.inner.$method(v)
+        }
+    )*};
+}
+
+impl<'de, V: Visitor<'de>> Visitor<'de> for FromTable<'_, V> {
+    type Value = V::Value;
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.inner.expecting(f)
+    }
+
+    forward_visit! {
+        visit_bool(bool);
+        visit_i8(i8);
+        visit_i16(i16);
+        visit_i32(i32);
+        visit_i64(i64);
+        visit_i128(i128);
+        visit_u8(u8);
+        visit_u16(u16);
+        visit_u32(u32);
+        visit_u64(u64);
+        visit_u128(u128);
+        visit_f32(f32);
+        visit_f64(f64);
+        visit_char(char);
+        visit_str(&str);
+        visit_borrowed_str(&'de str);
+        visit_string(String);
+        visit_bytes(&[u8]);
+        visit_borrowed_bytes(&'de [u8]);
+        visit_byte_buf(Vec<u8>);
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<V::Value, E> {
+        self.inner.visit_none()
+    }
+    fn visit_some<D: Deserializer<'de>>(self, d: D) -> Result<V::Value, D::Error> {
+        let d = self.wrap(d);
+        self.inner.visit_some(d)
+    }
+    fn visit_unit<E: de::Error>(self) -> Result<V::Value, E> {
+        self.inner.visit_unit()
+    }
+    fn visit_newtype_struct<D: Deserializer<'de>>(self, d: D) -> Result<V::Value, D::Error> {
+        let d = self.wrap(d);
+        self.inner.visit_newtype_struct(d)
+    }
+    fn visit_seq<A: SeqAccess<'de>>(self, seq: A) -> Result<V::Value, A::Error> {
+        let seq = self.wrap(seq);
+        self.inner.visit_seq(seq)
+    }
+    fn visit_map<A: de::MapAccess<'de>>(self, map: A) -> Result<V::Value, A::Error> {
+        let map = self.wrap(map);
+        self.inner.visit_map(map)
+    }
+    fn visit_enum<A: de::EnumAccess<'de>>(self, data: A) -> Result<V::Value, A::Error> {
+        let data = self.wrap(data);
+        self.inner.visit_enum(data)
+    }
+}
+
+impl<'de, A: SeqAccess<'de>> SeqAccess<'de> for FromTable<'_, A> {
+    type Error = A::Error;
+    #[inline]
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, A::Error> {
+        let seed = self.wrap(seed);
+        self.inner.next_element_seed(seed)
+    }
+    #[inline]
+    fn size_hint(&self) -> Option<usize> {
+        self.inner.size_hint()
+    }
+}
+
+impl<'de, A: de::MapAccess<'de>> de::MapAccess<'de> for FromTable<'_, A> {
+    type Error = A::Error;
+    #[inline]
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, A::Error> {
+        let seed = self.wrap(seed);
+        self.inner.next_key_seed(seed)
+    }
+    #[inline]
+    fn next_value_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<T::Value, A::Error> {
+        let seed = self.wrap(seed);
+        self.inner.next_value_seed(seed)
+    }
+    #[inline]
+    fn size_hint(&self) -> Option<usize> {
+        self.inner.size_hint()
+    }
+}
+
+impl<'t, 'de, A: de::EnumAccess<'de>> de::EnumAccess<'de> for FromTable<'t, A> {
+    type Error = A::Error;
+    type Variant = FromTable<'t, A::Variant>;
+    #[inline]
+    fn variant_seed<T: DeserializeSeed<'de>>(
+        self,
+        seed: T,
+    ) -> Result<(T::Value, Self::Variant), A::Error> {
+        let seed = self.wrap(seed);
+        let (v, variant) = self.inner.variant_seed(seed)?;
+        Ok((
+            v,
+            FromTable {
+                inner: variant,
+                table: self.table,
+            },
+        ))
+    }
+}
+
+impl<'de, A: de::VariantAccess<'de>> de::VariantAccess<'de> for FromTable<'_, A> {
+    type Error = A::Error;
+    #[inline]
+    fn unit_variant(self) -> Result<(), A::Error> {
+        self.inner.unit_variant()
+    }
+    #[inline]
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value, A::Error> {
+        let seed = self.wrap(seed);
+        self.inner.newtype_variant_seed(seed)
+    }
+    #[inline]
+    fn tuple_variant<V: Visitor<'de>>(self, len: usize, visitor: V) -> Result<V::Value, A::Error> {
+        let visitor = self.wrap(visitor);
+        self.inner.tuple_variant(len, visitor)
+    }
+    #[inline]
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, A::Error> {
+        let visitor = self.wrap(visitor);
+        self.inner.struct_variant(fields, visitor)
+    }
+}
+
+/// A `Serializer` which writes nothing, used to walk a value so that every
+/// `ArcStr` inside it gets added to the table.
+#[derive(Clone, Copy)]
+struct Collector<'a> {
+    strings: &'a RefCell<Strings>,
+    human_readable: bool,
+}
+
+#[derive(Debug)]
+struct CollectError(String);
+
+impl fmt::Display for CollectError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for CollectError {}
+
+impl ser::Error for CollectError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        CollectError(msg.to_string())
+    }
+}
+
+type Done = Result<(), CollectError>;
+
+impl Serializer for Collector<'_> {
+    type Ok = ();
+    type Error = CollectError;
+    type SerializeSeq = Self;
+    type SerializeTuple = Self;
+    type SerializeTupleStruct = Self;
+    type SerializeTupleVariant = Self;
+    type SerializeMap = Self;
+    type SerializeStruct = Self;
+    type SerializeStructVariant = Self;
+
+    fn serialize_bool(self, _: bool) -> Done {
+        Ok(())
+    }
+    fn serialize_i8(self, _: i8) -> Done {
+        Ok(())
+    }
+    fn serialize_i16(self, _: i16) -> Done {
+        Ok(())
+    }
+    fn serialize_i32(self, _: i32) -> Done {
+        Ok(())
+    }
+    fn serialize_i64(self, _: i64) -> Done {
+        Ok(())
+    }
+    fn serialize_i128(self, _: i128) -> Done {
+        Ok(())
+    }
+    fn serialize_u8(self, _: u8) -> Done {
+        Ok(())
+    }
+    fn serialize_u16(self, _: u16) -> Done {
+        Ok(())
+    }
+    fn serialize_u32(self, _: u32) -> Done {
+        Ok(())
+    }
+    fn serialize_u64(self, _: u64) -> Done {
+        Ok(())
+    }
+    fn serialize_u128(self, _: u128) -> Done {
+        Ok(())
+    }
+    fn serialize_f32(self, _: f32) -> Done {
+        Ok(())
+    }
+    fn serialize_f64(self, _: f64) -> Done {
+        Ok(())
+    }
+    fn serialize_char(self, _: char) -> Done {
+        Ok(())
+    }
+    fn serialize_str(self, v: &str) -> Done {
+        self.strings
+            .borrow_mut()
+            .insert(v)
+            .map_err(ser::Error::custom)
+    }
+    fn serialize_bytes(self, _: &[u8]) -> Done {
+        Ok(())
+    }
+    fn serialize_none(self) -> Done {
+        Ok(())
+    }
+    fn serialize_some<T: ?Sized + Serialize>(self, v: &T) -> Done {
+        v.serialize(self)
+    }
+    fn serialize_unit(self) -> Done {
+        Ok(())
+    }
+    fn serialize_unit_struct(self, _: &'static str) -> Done {
+        Ok(())
+    }
+    fn serialize_unit_variant(self, _: &'static str, _: u32, _: &'static str) -> Done {
+        Ok(())
+    }
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(self, _: &'static str, v: &T) -> Done {
+        v.serialize(self)
+    }
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        v: &T,
+    ) -> Done {
+        v.serialize(self)
+    }
+    fn serialize_seq(self, _: Option<usize>) -> Result<Self, CollectError> {
+        Ok(self)
+    }
+    fn serialize_tuple(self, _: usize) -> Result<Self, CollectError> {
+        Ok(self)
+    }
+    fn serialize_tuple_struct(self, _: &'static str, _: usize) -> Result<Self, CollectError> {
+        Ok(self)
+    }
+    fn serialize_tuple_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self, CollectError> {
+        Ok(self)
+    }
+    fn serialize_map(self, _: Option<usize>) -> Result<Self, CollectError> {
+        Ok(self)
+    }
+    fn serialize_struct(self, _: &'static str, _: usize) -> Result<Self, CollectError> {
+        Ok(self)
+    }
+    fn serialize_struct_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self, CollectError> {
+        Ok(self)
+    }
+    fn collect_str<T: ?Sized + fmt::Display>(self, v: &T) -> Done {
+        self.serialize_str(&v.to_string())
+    }
+    fn is_human_readable(&self) -> bool {
+        self.human_readable
+    }
+}
+
+impl ser::SerializeSeq for Collector<'_> {
+    type Ok = ();
+    type Error = CollectError;
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, v: &T) -> Done {
+        v.serialize(*self)
+    }
+    fn end(self) -> Done {
+        Ok(())
+    }
+}
+
+impl ser::SerializeTuple for Collector<'_> {
+    type Ok = ();
+    type Error = CollectError;
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, v: &T) -> Done {
+        v.serialize(*self)
+    }
+    fn end(self) -> Done {
+        Ok(())
+    }
+}
+
+impl ser::SerializeTupleStruct for Collector<'_> {
+    type Ok = ();
+    type Error = CollectError;
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, v: &T) -> Done {
+        v.serialize(*self)
+    }
+    fn end(self) -> Done {
+        Ok(())
+    }
+}
+
+impl ser::SerializeTupleVariant for Collector<'_> {
+    type Ok = ();
+    type Error = CollectError;
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, v: &T) -> Done {
+        v.serialize(*self)
+    }
+    fn end(self) -> Done {
+        Ok(())
+    }
+}
+
+impl ser::SerializeMap for Collector<'_> {
+    type Ok = ();
+    type Error = CollectError;
+    fn serialize_key<T: ?Sized + Serialize>(&mut self, k: &T) -> Done {
+        k.serialize(*self)
+    }
+    fn serialize_value<T: ?Sized + Serialize>(&mut self, v: &T) -> Done {
+        v.serialize(*self)
+    }
+    fn end(self) -> Done {
+        Ok(())
+    }
+}
+
+impl ser::SerializeStruct for Collector<'_> {
+    type Ok = ();
+    type Error = CollectError;
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, _: &'static str, v: &T) -> Done {
+        v.serialize(*self)
+    }
+    fn end(self) -> Done {
+        Ok(())
+    }
+}
+
+impl ser::SerializeStructVariant for Collector<'_> {
+    type Ok = ();
+    type Error = CollectError;
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, _: &'static str, v: &T) -> Done {
+        v.serialize(*self)
+    }
+    fn end(self) -> Done {
+        Ok(())
+    }
+}
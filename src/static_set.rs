@@ -0,0 +1,324 @@
+use crate::ArcStr;
+
+/// A compile-time set of static `ArcStr`s, which can be searched by content.
+///
+/// This is created with the [`static_set!`](crate::static_set) macro, which
+/// builds a perfect hash table over the entries at compile time, so lookups
+/// are a single hash and string comparison, and the table itself lives in the
+/// read-only data of your executable.
+///
+/// It's intended for the common case of a field holding one of a handful of
+/// well-known values (HTTP methods, log levels, etc). Looking a string up with
+/// [`StaticSet::get`] or [`ArcStr::from_known`] returns the static `ArcStr`
+/// when it's one of the entries, which means no allocation is needed.
+///
+/// With the `serde` feature, `&StaticSet` is also a
+/// [`DeserializeSeed`](::serde::de::DeserializeSeed), which does the same
+/// while deserializing.
+///
+/// # Examples
+///
+/// ```
+/// # use arcstr::{ArcStr, StaticSet};
+/// arcstr::static_set! {
+///     pub static METHODS = ["GET", "POST", "PUT", "DELETE"];
+/// }
+///
+/// let get = METHODS.get("GET").unwrap();
+/// assert!(ArcStr::is_static(get));
+/// assert!(METHODS.get("PATCH").is_none());
+///
+/// let s = ArcStr::from_known("POST", &METHODS);
+/// assert!(ArcStr::is_static(&s));
+/// let s = ArcStr::from_known("PATCH", &METHODS);
+/// assert!(!ArcStr::is_static(&s));
+/// ```
+pub struct StaticSet<const N: usize> {
+    entries: [ArcStr; N],
+    seed: u64,
+    // Indexed by bucket.
+    disps: [(u32, u32); N],
+    // Indexed by slot, holds an index into `entries`.
+    slots: [u32; N],
+}
+
+impl<const N: usize> StaticSet<N> {
+    /// Find the entry equal to `s`, if there is one.
+    #[inline]
+    pub fn get(&self, s: &str) -> Option<&ArcStr> {
+        if N == 0 {
+            return None;
+        }
+        let h = Hashes::new(s.as_bytes(), self.seed, N);
+        let (d1, d2) = self.disps[h.bucket];
+        let entry = &self.entries[self.slots[h.slot(d1, d2, N)] as usize];
+        if entry.as_str() == s {
+            Some(entry)
+        } else {
+            None
+        }
+    }
+
+    /// Returns true if one of the entries is equal to `s`.
+    #[inline]
+    pub fn contains(&self, s: &str) -> bool {
+        self.get(s).is_some()
+    }
+
+    /// Returns the entries, in the order they were given to `static_set!`.
+    #[inline]
+    pub fn as_slice(&self) -> &[ArcStr] {
+        &self.entries
+    }
+
+    /// Returns the number of entries.
+    #[inline]
+    pub const fn len(&self) -> usize {
+        N
+    }
+
+    /// Returns true if there are no entries.
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        N == 0
+    }
+}
+
+impl<const N: usize> core::fmt::Debug for StaticSet<N> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_set().entries(self.entries.iter()).finish()
+    }
+}
+
+/// The values we derive from a key's hash. `bucket` picks a displacement pair
+/// `(d1, d2)`, and the key lives in `slot(d1, d2)`.
+struct Hashes {
+    bucket: usize,
+    f1: u64,
+    f2: u64,
+}
+
+impl Hashes {
+    const fn new(bytes: &[u8], seed: u64, n: usize) -> Self {
+        // FNV-1a, followed by a couple of rounds of the `fmix64` finalizer from
+        // MurmurHash3 to spread the bits out.
+        let mut h = 0xcbf2_9ce4_8422_2325 ^ seed;
+        let mut i = 0;
+        while i < bytes.len() {
+            h ^= bytes[i] as u64;
+            h = h.wrapping_mul(0x0100_0000_01b3);
+            i += 1;
+        }
+        let h1 = fmix64(h);
+        let h2 = fmix64(h1 ^ 0x9e37_79b9_7f4a_7c15);
+        let n = n as u64;
+        Self {
+            bucket: ((h1 & 0xffff_ffff) % n) as usize,
+            f1: (h1 >> 32) % n,
+            f2: h2 % n,
+        }
+    }
+
+    #[inline]
+    const fn slot(&self, d1: u32, d2: u32, n: usize) -> usize {
+        // All of these are less than `n`, which is at most `u32::MAX`, so this
+        // can't overflow.
+        ((self.f1 + (d1 as u64) * self.f2 + d2 as u64) % (n as u64)) as usize
+    }
+}
+
+const fn fmix64(mut h: u64) -> u64 {
+    h ^= h >> 33;
+    h = h.wrapping_mul(0xff51_afd7_ed55_8ccd);
+    h ^= h >> 33;
+    h = h.wrapping_mul(0xc4ce_b9fe_1a85_ec53);
+    h ^= h >> 33;
+    h
+}
+
+const fn bytes_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut i = 0;
+    while i < a.len() {
+        if a[i] != b[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+// Not public API, exists for `static_set!`.
+//
+// Safety: every entry must be a static `ArcStr` (the macro creates them with
+// `literal_concat!`).
+#[doc(hidden)]
+pub const unsafe fn build_static_set<const N: usize>(entries: [ArcStr; N]) -> StaticSet<N> {
+    let mut keys: [&[u8]; N] = [&[]; N];
+    let mut i = 0;
+    while i < N {
+        keys[i] = entries[i].static_bytes();
+        i += 1;
+    }
+    assert!(N <= u32::MAX as usize, "too many entries in `static_set!`");
+    let mut seed = 0;
+    loop {
+        if let Some((disps, slots)) = try_build(&keys, seed) {
+            return StaticSet {
+                entries,
+                seed,
+                disps,
+                slots,
+            };
+        }
+        seed += 1;
+        if seed == 64 {
+            panic!("failed to build a perfect hash table for `static_set!`");
+        }
+    }
+}
+
+// This is the "hash, displace, and compress" (CHD) algorithm, minus the
+// compression, and with one bucket per key. Keys are split into buckets by
+// their hash, and then, largest bucket first, we search for a displacement
+// pair that puts every key in the bucket into an unused slot.
+//
+// This is all done during const evaluation, which is slow, so we try to keep
+// the amount of work linear-ish in the number of keys. Notably, most keys are
+// alone in their bucket, and those are placed directly rather than searched
+// for.
+#[allow(clippy::type_complexity)]
+const fn try_build<const N: usize>(
+    keys: &[&[u8]; N],
+    seed: u64,
+) -> Option<([(u32, u32); N], [u32; N])> {
+    const EMPTY: u32 = u32::MAX;
+    let mut hashes = [(0u64, 0u64); N];
+    let mut bucket_len = [0usize; N];
+    let mut max_len = 0;
+    let mut i = 0;
+    while i < N {
+        let h = Hashes::new(keys[i], seed, N);
+        hashes[i] = (h.f1, h.f2);
+        bucket_len[h.bucket] += 1;
+        if bucket_len[h.bucket] > max_len {
+            max_len = bucket_len[h.bucket];
+        }
+        i += 1;
+    }
+    // Group the keys by bucket: the members of bucket `b` are
+    // `members[bucket_start[b]..][..bucket_len[b]]`.
+    let mut bucket_start = [0usize; N];
+    let mut fill = [0usize; N];
+    let mut pos = 0;
+    let mut b = 0;
+    while b < N {
+        bucket_start[b] = pos;
+        fill[b] = pos;
+        pos += bucket_len[b];
+        b += 1;
+    }
+    let mut members = [0usize; N];
+    i = 0;
+    while i < N {
+        let b = Hashes::new(keys[i], seed, N).bucket;
+        members[fill[b]] = i;
+        fill[b] += 1;
+        i += 1;
+    }
+    // Identical keys always share a bucket (and everything else), and would
+    // make us fail for every seed, so check for them here.
+    b = 0;
+    while b < N {
+        let mut x = bucket_start[b];
+        while x < bucket_start[b] + bucket_len[b] {
+            let mut y = bucket_start[b];
+            while y < x {
+                let (kx, ky) = (members[x], members[y]);
+                if hashes[kx].0 == hashes[ky].0
+                    && hashes[kx].1 == hashes[ky].1
+                    && bytes_eq(keys[kx], keys[ky])
+                {
+                    panic!("duplicate entry in `static_set!`");
+                }
+                y += 1;
+            }
+            x += 1;
+        }
+        b += 1;
+    }
+
+    let mut disps = [(0u32, 0u32); N];
+    let mut slots = [EMPTY; N];
+    let mut next_free = 0;
+    let mut len = max_len;
+    while len > 0 {
+        let mut bucket = 0;
+        while bucket < N {
+            if bucket_len[bucket] != len {
+                bucket += 1;
+                continue;
+            }
+            let first = bucket_start[bucket];
+            if len == 1 {
+                // Singletons can go in any free slot, so we don't need to
+                // search. They're placed last, so `next_free` only moves
+                // forward.
+                while slots[next_free] != EMPTY {
+                    next_free += 1;
+                }
+                let f1 = hashes[members[first]].0 as usize;
+                disps[bucket] = (0, ((next_free + N - f1) % N) as u32);
+                slots[next_free] = members[first] as u32;
+                bucket += 1;
+                continue;
+            }
+            let mut placed = false;
+            let mut d1 = 0;
+            'search: while d1 < N as u32 {
+                let mut d2 = 0;
+                while d2 < N as u32 {
+                    // Check that every member lands in a free slot, distinct
+                    // from the other members.
+                    let mut ok = true;
+                    let mut a = 0;
+                    while ok && a < len {
+                        let sa = slot_of(hashes[members[first + a]], d1, d2, N);
+                        ok = slots[sa] == EMPTY;
+                        let mut b = 0;
+                        while ok && b < a {
+                            ok = slot_of(hashes[members[first + b]], d1, d2, N) != sa;
+                            b += 1;
+                        }
+                        a += 1;
+                    }
+                    if ok {
+                        let mut a = 0;
+                        while a < len {
+                            let k = members[first + a];
+                            slots[slot_of(hashes[k], d1, d2, N)] = k as u32;
+                            a += 1;
+                        }
+                        disps[bucket] = (d1, d2);
+                        placed = true;
+                        break 'search;
+                    }
+                    d2 += 1;
+                }
+                d1 += 1;
+            }
+            if !placed {
+                return None;
+            }
+            bucket += 1;
+        }
+        len -= 1;
+    }
+    Some((disps, slots))
+}
+
+const fn slot_of((f1, f2): (u64, u64), d1: u32, d2: u32, n: usize) -> usize {
+    Hashes { bucket: 0, f1, f2 }.slot(d1, d2, n)
+}
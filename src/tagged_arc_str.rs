@@ -0,0 +1,262 @@
+use crate::ArcStr;
+use core::marker::PhantomData;
+use core::mem::ManuallyDrop;
+use core::ptr::NonNull;
+
+/// An [`ArcStr`] with a small tag packed into the unused low bits of its
+/// pointer.
+///
+/// The pointer inside an `ArcStr` is always at least 8-byte aligned (as
+/// documented on [`ArcStr::into_raw`]), so its low 3 bits are always zero.
+/// `TaggedArcStr<BITS>` stores a `BITS`-bit tag there, which allows you to
+/// keep something like a small enum next to a string without paying for
+/// another word.
+///
+/// `BITS` must be at most 3, which is checked at compile time. Tags are `u8`s,
+/// and must fit in `BITS` bits. Functions which accept a tag will panic if it
+/// doesn't.
+///
+/// Equality, ordering and hashing consider both the string and the tag.
+///
+/// # Examples
+///
+/// ```
+/// # use arcstr::{ArcStr, TaggedArcStr};
+/// let mut s = TaggedArcStr::<2>::new(ArcStr::from("abc"), 3);
+/// assert_eq!(std::mem::size_of_val(&s), std::mem::size_of::<ArcStr>());
+/// assert_eq!(s.tag(), 3);
+/// assert_eq!(s, TaggedArcStr::new(ArcStr::from("abc"), 3));
+///
+/// s.set_tag(1);
+/// assert_eq!(s.tag(), 1);
+/// assert_eq!(s.as_str(), "abc");
+///
+/// let (s, tag) = s.into_parts();
+/// assert_eq!((s.as_str(), tag), ("abc", 1));
+/// ```
+pub struct TaggedArcStr<const BITS: u32> {
+    // The pointer from `ArcStr::into_raw`, plus the tag. We add and subtract
+    // the tag (rather than casting through `usize`) to preserve provenance.
+    ptr: NonNull<u8>,
+}
+
+// Safety: we're an `ArcStr`, which is `Send` and `Sync`.
+unsafe impl<const BITS: u32> Send for TaggedArcStr<BITS> {}
+unsafe impl<const BITS: u32> Sync for TaggedArcStr<BITS> {}
+
+impl<const BITS: u32> TaggedArcStr<BITS> {
+    const MASK: usize = {
+        assert!(BITS <= 3, "`TaggedArcStr` supports at most 3 tag bits");
+        (1 << BITS) - 1
+    };
+
+    /// The largest tag which can be stored.
+    pub const MAX_TAG: u8 = Self::MASK as u8;
+
+    /// Combine `s` and `tag` into a `TaggedArcStr`.
+    ///
+    /// # Panics
+    ///
+    /// If `tag` is larger than [`Self::MAX_TAG`].
+    #[inline]
+    pub fn new(s: ArcStr, tag: u8) -> Self {
+        Self::check_tag(tag);
+        let p = ArcStr::into_raw(s).cast::<u8>();
+        debug_assert_eq!(p.as_ptr() as usize & 7, 0);
+        Self {
+            // Safety: adding to a non-null pointer with spare alignment bits
+            // can't produce null.
+            ptr: unsafe { NonNull::new_unchecked(p.as_ptr().wrapping_add(tag as usize)) },
+        }
+    }
+
+    /// Returns the tag.
+    #[inline]
+    pub fn tag(&self) -> u8 {
+        (self.ptr.as_ptr() as usize & Self::MASK) as u8
+    }
+
+    /// Replace the tag, without touching the string.
+    ///
+    /// # Panics
+    ///
+    /// If `tag` is larger than [`Self::MAX_TAG`].
+    #[inline]
+    pub fn set_tag(&mut self, tag: u8) {
+        Self::check_tag(tag);
+        let untagged = self.untagged().cast::<u8>();
+        // Safety: same as in `new`.
+        self.ptr = unsafe { NonNull::new_unchecked(untagged.as_ptr().wrapping_add(tag as usize)) };
+    }
+
+    /// Returns a reference to the string as an `ArcStr`.
+    ///
+    /// This returns a guard rather than an `&ArcStr`, since there isn't an
+    /// untagged `ArcStr` in memory for us to reference.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use arcstr::{ArcStr, TaggedArcStr};
+    /// let a = ArcStr::from("abc");
+    /// let t = TaggedArcStr::<1>::new(a.clone(), 1);
+    /// assert!(ArcStr::ptr_eq(&t.as_arcstr(), &a));
+    /// let b: ArcStr = t.as_arcstr().clone();
+    /// assert_eq!(ArcStr::strong_count(&b), Some(3));
+    /// ```
+    #[inline]
+    pub fn as_arcstr(&self) -> TaggedArcStrRef<'_> {
+        TaggedArcStrRef {
+            // Safety: the pointer came from `ArcStr::into_raw`, and the
+            // `ManuallyDrop` prevents a double-drop.
+            s: ManuallyDrop::new(unsafe { ArcStr::from_raw(self.untagged()) }),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Extract a string slice containing our data.
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        // Safety: the pointer came from `ArcStr::into_raw`, and the
+        // `ManuallyDrop` prevents a double-drop.
+        let s = ManuallyDrop::new(unsafe { ArcStr::from_raw(self.untagged()) });
+        // Safety: the data is kept alive by `self`, not by `s`.
+        unsafe { &*(s.as_str() as *const str) }
+    }
+
+    /// Unwrap this into its `ArcStr`, discarding the tag.
+    #[inline]
+    pub fn into_arcstr(self) -> ArcStr {
+        self.into_parts().0
+    }
+
+    /// Unwrap this into its `ArcStr` and its tag.
+    #[inline]
+    pub fn into_parts(self) -> (ArcStr, u8) {
+        let this = ManuallyDrop::new(self);
+        let tag = this.tag();
+        // Safety: the pointer came from `ArcStr::into_raw`, and we won't drop
+        // `this`.
+        (unsafe { ArcStr::from_raw(this.untagged()) }, tag)
+    }
+
+    #[inline]
+    fn untagged(&self) -> NonNull<()> {
+        let tag = self.ptr.as_ptr() as usize & Self::MASK;
+        // Safety: this undoes the addition in `new`/`set_tag`.
+        unsafe { NonNull::new_unchecked(self.ptr.as_ptr().wrapping_sub(tag).cast()) }
+    }
+
+    #[inline]
+    #[track_caller]
+    fn check_tag(tag: u8) {
+        assert!(
+            tag as usize <= Self::MASK,
+            "tag {} doesn't fit in {} bits",
+            tag,
+            BITS,
+        );
+    }
+}
+
+impl<const BITS: u32> Clone for TaggedArcStr<BITS> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self::new(ArcStr::clone(&self.as_arcstr()), self.tag())
+    }
+}
+
+impl<const BITS: u32> Drop for TaggedArcStr<BITS> {
+    #[inline]
+    fn drop(&mut self) {
+        // Safety: the pointer came from `ArcStr::into_raw`.
+        drop(unsafe { ArcStr::from_raw(self.untagged()) });
+    }
+}
+
+impl<const BITS: u32> core::ops::Deref for TaggedArcStr<BITS> {
+    type Target = str;
+    #[inline]
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl<const BITS: u32> From<ArcStr> for TaggedArcStr<BITS> {
+    /// Wraps `s` with a tag of zero.
+    #[inline]
+    fn from(s: ArcStr) -> Self {
+        Self::new(s, 0)
+    }
+}
+
+impl<const BITS: u32> From<TaggedArcStr<BITS>> for ArcStr {
+    #[inline]
+    fn from(s: TaggedArcStr<BITS>) -> Self {
+        s.into_arcstr()
+    }
+}
+
+impl<const BITS: u32> core::fmt::Debug for TaggedArcStr<BITS> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("TaggedArcStr")
+            .field("str", &self.as_str())
+            .field("tag", &self.tag())
+            .finish()
+    }
+}
+
+impl<const BITS: u32> PartialEq for TaggedArcStr<BITS> {
+    #[inline]
+    fn eq(&self, o: &Self) -> bool {
+        self.tag() == o.tag() && *self.as_arcstr() == *o.as_arcstr()
+    }
+}
+
+impl<const BITS: u32> Eq for TaggedArcStr<BITS> {}
+
+impl<const BITS: u32> PartialOrd for TaggedArcStr<BITS> {
+    #[inline]
+    fn partial_cmp(&self, o: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(o))
+    }
+}
+
+impl<const BITS: u32> Ord for TaggedArcStr<BITS> {
+    #[inline]
+    fn cmp(&self, o: &Self) -> core::cmp::Ordering {
+        (self.as_str(), self.tag()).cmp(&(o.as_str(), o.tag()))
+    }
+}
+
+impl<const BITS: u32> core::hash::Hash for TaggedArcStr<BITS> {
+    #[inline]
+    fn hash<H: core::hash::Hasher>(&self, h: &mut H) {
+        self.as_str().hash(h);
+        self.tag().hash(h);
+    }
+}
+
+/// A reference to the [`ArcStr`] inside of a [`TaggedArcStr`], returned by
+/// [`TaggedArcStr::as_arcstr`].
+///
+/// This derefs to `ArcStr`, so it can be used like an `&ArcStr`.
+pub struct TaggedArcStrRef<'a> {
+    s: ManuallyDrop<ArcStr>,
+    _marker: PhantomData<&'a ArcStr>,
+}
+
+impl core::ops::Deref for TaggedArcStrRef<'_> {
+    type Target = ArcStr;
+    #[inline]
+    fn deref(&self) -> &ArcStr {
+        &self.s
+    }
+}
+
+impl core::fmt::Debug for TaggedArcStrRef<'_> {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        core::fmt::Debug::fmt(&**self, f)
+    }
+}
@@ -0,0 +1,220 @@
+use crate::ArcStr;
+use alloc::string::String;
+
+/// A uniquely-owned, mutable string, which can be converted into an
+/// [`ArcStr`] for free.
+///
+/// `ArcStr` is deliberately immutable, but it's common to want to edit a
+/// freshly built string in place before sharing it. A `UniqueArcStr` has the
+/// same representation as an `ArcStr`, but is guaranteed to be the only
+/// reference to its data, which allows it to implement `DerefMut<Target =
+/// str>`.
+///
+/// Once you're done mutating it, convert it to an `ArcStr` with
+/// [`UniqueArcStr::into_arcstr`] (or `From`/`Into`). This doesn't allocate or
+/// copy anything. Going the other way is possible using
+/// [`ArcStr::try_unique`], which succeeds if the `ArcStr` has no other
+/// references.
+///
+/// # Examples
+///
+/// ```
+/// # use arcstr::{ArcStr, UniqueArcStr};
+/// let mut s = UniqueArcStr::from("hello, world");
+/// s.make_ascii_uppercase();
+/// let s: ArcStr = s.into_arcstr();
+/// assert_eq!(s, "HELLO, WORLD");
+/// ```
+#[repr(transparent)]
+pub struct UniqueArcStr(ArcStr);
+
+impl UniqueArcStr {
+    /// Construct a new empty string.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use arcstr::UniqueArcStr;
+    /// let s = UniqueArcStr::new();
+    /// assert_eq!(s, "");
+    /// ```
+    #[inline]
+    pub const fn new() -> Self {
+        Self(ArcStr::new())
+    }
+
+    // Safety: `s` must be empty, or a heap-allocated `ArcStr` with no other
+    // references.
+    #[inline]
+    pub(crate) unsafe fn from_arcstr_unchecked(s: ArcStr) -> Self {
+        debug_assert!(s.is_empty() || s.is_unique());
+        Self(s)
+    }
+
+    /// Extract a string slice containing our data.
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Extract a mutable string slice containing our data.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use arcstr::UniqueArcStr;
+    /// let mut s = UniqueArcStr::from("abc");
+    /// s.as_mut_str().make_ascii_uppercase();
+    /// assert_eq!(s, "ABC");
+    /// ```
+    #[inline]
+    pub fn as_mut_str(&mut self) -> &mut str {
+        let len = self.0.len();
+        if len == 0 {
+            // The empty string is static, so it isn't really ours to hand out
+            // a `&mut` into.
+            return <&mut str>::default();
+        }
+        unsafe {
+            let bytes = core::slice::from_raw_parts_mut(self.0.data_ptr_mut(), len);
+            core::str::from_utf8_unchecked_mut(bytes)
+        }
+    }
+
+    /// Convert this into an `ArcStr`, without allocating or copying.
+    ///
+    /// The result starts with a reference count of 1, and can be cloned and
+    /// shared as usual from there.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use arcstr::{ArcStr, UniqueArcStr};
+    /// let s = UniqueArcStr::from("abc");
+    /// let a: ArcStr = s.into_arcstr();
+    /// assert_eq!(ArcStr::strong_count(&a), Some(1));
+    /// ```
+    #[inline]
+    pub fn into_arcstr(self) -> ArcStr {
+        // Unsafe code may have changed whether we're ASCII through
+        // `as_mut_str`, so the cached flag can't be trusted anymore.
+        unsafe { self.0.refresh_ascii() };
+        self.0
+    }
+}
+
+impl core::ops::Deref for UniqueArcStr {
+    type Target = str;
+    #[inline]
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl core::ops::DerefMut for UniqueArcStr {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut str {
+        self.as_mut_str()
+    }
+}
+
+impl Default for UniqueArcStr {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<&str> for UniqueArcStr {
+    #[inline]
+    fn from(s: &str) -> Self {
+        // A freshly allocated `ArcStr` is always unique.
+        unsafe { Self::from_arcstr_unchecked(ArcStr::from(s)) }
+    }
+}
+
+impl From<String> for UniqueArcStr {
+    #[inline]
+    fn from(s: String) -> Self {
+        Self::from(s.as_str())
+    }
+}
+
+impl From<UniqueArcStr> for ArcStr {
+    #[inline]
+    fn from(s: UniqueArcStr) -> Self {
+        s.into_arcstr()
+    }
+}
+
+impl core::fmt::Debug for UniqueArcStr {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        core::fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl core::fmt::Display for UniqueArcStr {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        core::fmt::Display::fmt(self.as_str(), f)
+    }
+}
+
+impl AsRef<str> for UniqueArcStr {
+    #[inline]
+    fn as_ref(&self) -> &str {
+        self
+    }
+}
+
+impl AsMut<str> for UniqueArcStr {
+    #[inline]
+    fn as_mut(&mut self) -> &mut str {
+        self
+    }
+}
+
+impl core::borrow::Borrow<str> for UniqueArcStr {
+    #[inline]
+    fn borrow(&self) -> &str {
+        self
+    }
+}
+
+impl core::borrow::BorrowMut<str> for UniqueArcStr {
+    #[inline]
+    fn borrow_mut(&mut self) -> &mut str {
+        self
+    }
+}
+
+impl PartialEq for UniqueArcStr {
+    #[inline]
+    fn eq(&self, o: &Self) -> bool {
+        self.as_str() == o.as_str()
+    }
+}
+
+impl Eq for UniqueArcStr {}
+
+impl PartialEq<str> for UniqueArcStr {
+    #[inline]
+    fn eq(&self, o: &str) -> bool {
+        self.as_str() == o
+    }
+}
+
+impl<'a> PartialEq<&'a str> for UniqueArcStr {
+    #[inline]
+    fn eq(&self, o: &&'a str) -> bool {
+        self.as_str() == *o
+    }
+}
+
+impl core::hash::Hash for UniqueArcStr {
+    #[inline]
+    fn hash<H: core::hash::Hasher>(&self, h: &mut H) {
+        self.as_str().hash(h)
+    }
+}
@@ -0,0 +1,138 @@
+use arcstr::{ArcCow, ArcStr};
+use std::borrow::Cow;
+use std::collections::HashSet;
+
+#[test]
+fn test_size() {
+    assert_eq!(
+        std::mem::size_of::<ArcCow<'_>>(),
+        std::mem::size_of::<&str>()
+    );
+    assert_eq!(
+        std::mem::size_of::<Option<ArcCow<'_>>>(),
+        std::mem::size_of::<&str>()
+    );
+}
+
+#[test]
+fn test_borrowed() {
+    let input = String::from("hello world");
+    let c = ArcCow::from(&input[..5]);
+    assert!(c.is_borrowed() && !c.is_owned());
+    assert_eq!(c, "hello");
+    assert_eq!(c.len(), 5);
+    assert!(!c.is_empty());
+    assert_eq!(c.as_borrowed().unwrap().as_ptr(), input.as_ptr());
+    assert!(c.as_arcstr().is_none());
+    let c2 = c.clone();
+    assert!(c2.is_borrowed());
+    assert_eq!(c, c2);
+    assert_eq!(c.into_owned(), "hello");
+
+    let e = ArcCow::default();
+    assert!(e.is_empty() && e.is_borrowed());
+    assert_eq!(e, ArcCow::new());
+}
+
+#[test]
+fn test_owned() {
+    let a = ArcStr::from("shared");
+    let c = ArcCow::from(a.clone());
+    assert!(c.is_owned());
+    assert_eq!(c.len(), 6);
+    assert_eq!(c, "shared");
+    assert!(ArcStr::ptr_eq(c.as_arcstr().unwrap(), &a));
+    assert_eq!(ArcStr::strong_count(&a), Some(2));
+    let c2 = c.clone();
+    assert_eq!(ArcStr::strong_count(&a), Some(3));
+    drop(c);
+    assert_eq!(ArcStr::strong_count(&a), Some(2));
+    let b = c2.into_owned();
+    assert!(ArcStr::ptr_eq(&a, &b));
+    assert_eq!(ArcStr::strong_count(&a), Some(2));
+    drop(b);
+    assert_eq!(ArcStr::strong_count(&a), Some(1));
+
+    let s: ArcCow<'static> = {
+        let tmp = String::from("temporary");
+        ArcCow::from(&tmp[..]).into_static()
+    };
+    assert!(s.is_owned());
+    assert_eq!(s, "temporary");
+}
+
+#[test]
+fn test_conversions() {
+    let c: ArcCow<'_> = Cow::Borrowed("abc").into();
+    assert!(c.is_borrowed());
+    let c: ArcCow<'_> = Cow::<str>::Owned("abc".into()).into();
+    assert!(c.is_owned());
+    let cow: Cow<'_, str> = ArcCow::from("xyz").into();
+    assert!(matches!(cow, Cow::Borrowed("xyz")));
+    let cow: Cow<'_, str> = ArcCow::from(String::from("xyz")).into();
+    assert!(matches!(cow, Cow::Owned(_)));
+    let cow: Cow<'_, str> = ArcCow::from(arcstr::literal_concat!("xyz")).into();
+    assert!(matches!(cow, Cow::Borrowed("xyz")));
+    assert_eq!(String::from(ArcCow::from("s")), "s");
+    assert_eq!(ArcStr::from(ArcCow::from("s")), "s");
+    let a = ArcStr::from("abc");
+    assert!(ArcCow::from(&a).is_borrowed());
+    assert!("abc".parse::<ArcCow<'_>>().unwrap().is_owned());
+    let c: ArcCow<'_> = ["a", "b", "c"].iter().copied().collect();
+    assert_eq!(c, "abc");
+}
+
+#[test]
+fn test_traits() {
+    let b = ArcCow::from("Hello");
+    let o = ArcCow::from(ArcStr::from("Hello"));
+    assert_eq!(b, o);
+    assert_eq!(b, ArcStr::from("Hello"));
+    assert_eq!(String::from("Hello"), o);
+    assert_eq!(&b[1..], "ello");
+    assert_eq!(format!("{} {:?}", b, o), "Hello \"Hello\"");
+    let (lo, hi) = (ArcCow::from("a"), ArcCow::from(ArcStr::from("b")));
+    assert!(lo < hi);
+
+    let mut set = HashSet::new();
+    set.insert(b);
+    assert!(!set.insert(o));
+    assert!(set.contains("Hello"));
+}
+
+#[test]
+#[cfg(feature = "serde")]
+fn test_serde() {
+    let v: Vec<ArcCow<'_>> = serde_json::from_str(r#"["plain", "esc\"aped"]"#).unwrap();
+    assert!(v[0].is_borrowed());
+    assert_eq!(v[0], "plain");
+    assert!(v[1].is_owned());
+    assert_eq!(v[1], "esc\"aped");
+    assert_eq!(
+        serde_json::to_string(&v).unwrap(),
+        r#"["plain","esc\"aped"]"#
+    );
+}
+
+#[test]
+#[cfg(all(feature = "serde", feature = "std"))]
+fn test_serde_shared() {
+    use arcstr::serde::Shared;
+    let owned = ArcStr::from("x");
+    let v = Shared(vec![
+        ArcCow::borrowed("y"),
+        ArcCow::owned(owned.clone()),
+        ArcCow::owned(owned.clone()),
+        ArcCow::borrowed("y"),
+    ]);
+    let json = serde_json::to_string(&v).unwrap();
+    assert_eq!(json, r#"[["y","x"],[0,1,1,0]]"#);
+    let Shared(back): Shared<Vec<ArcCow<'_>>> = serde_json::from_str(&json).unwrap();
+    // Everything comes back owned, since it can't borrow from the table.
+    assert_eq!(back, ["y", "x", "x", "y"]);
+    assert!(back.iter().all(ArcCow::is_owned));
+
+    let bin = bincode::serialize(&v).unwrap();
+    let Shared(back): Shared<Vec<ArcCow<'_>>> = bincode::deserialize(&bin).unwrap();
+    assert_eq!(back, ["y", "x", "x", "y"]);
+}
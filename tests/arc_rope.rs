@@ -0,0 +1,135 @@
+use arcstr::{literal_concat, ArcRope, ArcStr};
+use std::collections::HashSet;
+
+fn pieces() -> Vec<ArcStr> {
+    ["Hello", ", ", "wörld", "", "!", " ünïcödé ", "tail"]
+        .iter()
+        .map(|s| ArcStr::from(*s))
+        .collect()
+}
+
+#[test]
+fn test_concat_and_index() {
+    let expected: String = pieces().iter().map(|s| s.as_str()).collect();
+    let rope: ArcRope = pieces().into_iter().collect();
+    assert_eq!(rope, *expected);
+    assert_eq!(rope.len(), expected.len());
+    assert_eq!(rope.char_count(), expected.chars().count());
+    assert_eq!(rope.to_string(), expected);
+    assert_eq!(format!("{:?}", rope), format!("{:?}", expected));
+    assert_eq!(format!("[{:>40}]", rope), format!("[{:>40}]", expected));
+    for (i, b) in expected.bytes().enumerate() {
+        assert_eq!(rope.byte_at(i), Some(b));
+        assert_eq!(rope.is_char_boundary(i), expected.is_char_boundary(i));
+    }
+    assert_eq!(rope.byte_at(expected.len()), None);
+    for (n, c) in expected.chars().enumerate() {
+        assert_eq!(rope.char_at(n), Some(c));
+    }
+    assert_eq!(rope.char_at(rope.char_count()), None);
+    assert!(rope.chars().eq(expected.chars()));
+    assert!(rope.chars().rev().eq(expected.chars().rev()));
+    assert_eq!(
+        rope.chunks().rev().collect::<Vec<_>>(),
+        ["tail", " ünïcödé ", "!", "wörld", ", ", "Hello"]
+    );
+    let mut chunks = rope.chunks();
+    assert_eq!(chunks.next(), Some("Hello"));
+    assert_eq!(chunks.next_back(), Some("tail"));
+    assert_eq!(chunks.next(), Some(", "));
+    assert_eq!(chunks.next_back(), Some(" ünïcödé "));
+    assert_eq!(chunks.next_back(), Some("!"));
+    assert_eq!(chunks.next(), Some("wörld"));
+    assert_eq!(chunks.next(), None);
+    assert_eq!(chunks.next_back(), None);
+
+    let flat = rope.flatten();
+    assert_eq!(flat, *expected);
+    assert_eq!(ArcStr::from(rope.clone()), flat);
+}
+
+#[test]
+fn test_slice() {
+    let expected: String = pieces().iter().map(|s| s.as_str()).collect();
+    let rope: ArcRope = pieces().into_iter().collect();
+    let bounds: Vec<usize> = (0..=expected.len())
+        .filter(|&i| expected.is_char_boundary(i))
+        .collect();
+    for &a in &bounds {
+        for &b in bounds.iter().filter(|&&b| b >= a) {
+            let s = rope.slice(a..b);
+            assert_eq!(s, expected[a..b]);
+            assert_eq!(s.char_count(), expected[a..b].chars().count());
+            let t = rope.slice(a..b);
+            assert_eq!(t.char_at(1), expected[a..b].chars().nth(1));
+            assert_eq!(s.flatten(), expected[a..b]);
+        }
+    }
+    assert_eq!(rope.slice(..), rope);
+    assert_eq!(rope.slice(..=4), "Hello");
+}
+
+#[test]
+#[should_panic(expected = "char boundaries")]
+fn test_slice_boundary() {
+    ArcRope::from("wörld").slice(..2);
+}
+
+#[test]
+#[should_panic(expected = "out of bounds")]
+fn test_slice_oob() {
+    ArcRope::from("abc").slice(1..4);
+}
+
+#[test]
+fn test_balanced() {
+    // Lots of tiny pieces, appended and prepended. An unbalanced tree would
+    // make this quadratic (and overflow the stack when dropped).
+    let a = ArcRope::from(literal_concat!("a"));
+    let b = ArcRope::from(literal_concat!("b"));
+    let mut rope = ArcRope::new();
+    for i in 0..50_000 {
+        rope = if i % 2 == 0 {
+            rope.concat(&a)
+        } else {
+            b.concat(&rope)
+        };
+    }
+    assert_eq!(rope.len(), 50_000);
+    assert_eq!(rope.byte_at(0), Some(b'b'));
+    assert_eq!(rope.byte_at(49_999), Some(b'a'));
+    assert_eq!(rope.char_at(25_000), Some('a'));
+    let flat = rope.flatten();
+    assert_eq!(&flat[..4], "bbbb");
+    assert_eq!(rope.slice(24_990..25_010).flatten(), flat[24_990..25_010]);
+
+    let big = (0..1000).map(|_| rope.clone()).collect::<ArcRope>();
+    assert_eq!(big.len(), 50_000_000);
+    assert_eq!(big.byte_at(49_999_999), Some(b'a'));
+}
+
+#[test]
+fn test_static_and_traits() {
+    const LIT: ArcStr = literal_concat!("static");
+    let r = ArcRope::from(LIT);
+    assert!(ArcStr::ptr_eq(&r.flatten(), &LIT));
+    assert!(ArcStr::is_static(&r.flatten()));
+    assert_eq!(r, LIT);
+    assert_eq!("static", r);
+    assert_eq!(r, String::from("static"));
+
+    assert!(ArcRope::new().is_empty());
+    assert_eq!(ArcRope::default(), "");
+    assert!(ArcStr::is_static(&ArcRope::new().flatten()));
+    assert_eq!(ArcRope::new().chunks().next(), None);
+
+    let split = ArcRope::from("sta").concat(&ArcRope::from("tic"));
+    assert_eq!(split, r);
+    // Ropes cache their char counts in atomics, which the hash ignores.
+    #[allow(clippy::mutable_key_type)]
+    let mut set = HashSet::new();
+    set.insert(r);
+    assert!(set.contains(&split));
+    let (lo, hi) = (ArcRope::from("ab"), ArcRope::from("a").concat(&"c".into()));
+    assert!(lo < hi);
+}
@@ -145,6 +145,161 @@ fn test_serde() {
     }
 }
 
+#[cfg(all(feature = "serde", feature = "std"))]
+#[test]
+fn test_serde_shared() {
+    use arcstr::serde::{interning, Shared};
+    use serde::{Deserialize, Serialize};
+    use std::collections::BTreeMap;
+
+    #[derive(Serialize, Deserialize)]
+    struct Words(#[serde(deserialize_with = "interning::deserialize_vec")] Vec<ArcStr>);
+
+    #[derive(Serialize, Deserialize)]
+    struct Node {
+        #[serde(deserialize_with = "interning::deserialize")]
+        name: ArcStr,
+        #[serde(deserialize_with = "interning::deserialize_option")]
+        tag: Option<ArcStr>,
+        // Only the keys are shared.
+        #[serde(deserialize_with = "interning::deserialize_map_keys")]
+        attrs: BTreeMap<ArcStr, ArcStr>,
+        children: Vec<Node>,
+        // Inner uses get a table of their own.
+        nested: Shared<Words>,
+    }
+
+    let div = ArcStr::from("div");
+    let class = ArcStr::from("class");
+    let other_div = ArcStr::from("div");
+    let leaf = |name: &ArcStr| Node {
+        name: name.clone(),
+        tag: Some(div.clone()),
+        attrs: vec![(class.clone(), div.clone())].into_iter().collect(),
+        children: vec![],
+        nested: Shared(Words(vec![class.clone(), class.clone()])),
+    };
+    let root = Shared(Node {
+        name: div.clone(),
+        tag: None,
+        attrs: BTreeMap::new(),
+        children: vec![leaf(&div), leaf(&other_div), leaf(&class)],
+        nested: Shared(Words(vec![])),
+    });
+
+    let check = |root: Node| {
+        let names: Vec<_> = root.children.iter().map(|c| &c.name).collect();
+        assert_eq!(names, ["div", "div", "class"]);
+        assert!(ArcStr::ptr_eq(&root.name, names[0]));
+        // Equal but distinct strings stay distinct.
+        assert!(!ArcStr::ptr_eq(names[0], names[1]));
+        for c in &root.children {
+            assert!(ArcStr::ptr_eq(c.tag.as_ref().unwrap(), &root.name));
+            let (k, v) = c.attrs.iter().next().unwrap();
+            assert!(ArcStr::ptr_eq(k, names[2]));
+            assert_eq!(v, "div");
+            assert!(!ArcStr::ptr_eq(v, &root.name));
+            let nested = &(c.nested.0).0;
+            assert!(ArcStr::ptr_eq(&nested[0], &nested[1]));
+        }
+        // The table is gone, leaving two names and three tags.
+        assert_eq!(ArcStr::strong_count(&root.name), Some(2 + 3));
+    };
+
+    let json = serde_json::to_string(&root).unwrap();
+    assert!(json.starts_with(r#"[["div","class","#), "{}", json);
+    check(serde_json::from_str::<Shared<Node>>(&json).unwrap().0);
+
+    let bin = bincode::serialize(&root).unwrap();
+    check(bincode::deserialize::<Shared<Node>>(&bin).unwrap().0);
+
+    // Other strings go in the table too, the same way.
+    let strings = Shared((String::from("a"), "a", 'b', ArcStr::from("a")));
+    let json = serde_json::to_string(&strings).unwrap();
+    assert_eq!(json, r#"[["a","a","a"],[0,1,"b",2]]"#);
+    let Shared(back): Shared<(String, String, char, ArcStr)> = serde_json::from_str(&json).unwrap();
+    assert_eq!(back, ("a".into(), "a".into(), 'b', ArcStr::from("a")));
+
+    assert!(serde_json::from_str::<Shared<Vec<ArcStr>>>(r#"[["a"],[0,1]]"#).is_err());
+    assert!(serde_json::from_str::<Shared<Vec<ArcStr>>>(r#"[["a"]]"#).is_err());
+}
+
+#[cfg(all(feature = "serde", feature = "std"))]
+#[test]
+fn test_serde_interning() {
+    use arcstr::serde::interning;
+    use serde::Deserialize;
+    use std::collections::{BTreeMap, BTreeSet, HashSet};
+
+    #[derive(Deserialize)]
+    struct Event {
+        #[serde(deserialize_with = "interning::deserialize")]
+        kind: ArcStr,
+        #[serde(deserialize_with = "interning::deserialize_option")]
+        user: Option<ArcStr>,
+        #[serde(deserialize_with = "interning::deserialize_vec")]
+        tags: Vec<ArcStr>,
+        #[serde(deserialize_with = "interning::deserialize_map_keys")]
+        fields: BTreeMap<ArcStr, u32>,
+    }
+
+    let input = r#"[
+        {"kind": "click", "user": "alice", "tags": ["ui", "click"], "fields": {"x": 1}},
+        {"kind": "click", "user": null, "tags": [], "fields": {"x": 2, "ui": 3}}
+    ]"#;
+    let mut table = HashSet::new();
+    let events: Vec<Event> =
+        interning::with_table(&mut table, || serde_json::from_str(input)).unwrap();
+    let click = table.get("click").unwrap();
+    assert!(ArcStr::ptr_eq(&events[0].kind, click));
+    assert!(ArcStr::ptr_eq(&events[1].kind, click));
+    assert!(ArcStr::ptr_eq(&events[0].tags[1], click));
+    assert!(ArcStr::ptr_eq(
+        &events[0].tags[0],
+        events[1].fields.keys().next().unwrap()
+    ));
+    assert_eq!(events[0].user.as_deref(), Some("alice"));
+    assert_eq!(events[1].user, None);
+    assert_eq!(events[1].fields["x"], 2);
+    assert_eq!(table.len(), 4);
+
+    // Strings from the table are reused by later calls, in other formats too.
+    let bin = bincode::serialize(&("alice", vec!["new"])).unwrap();
+    let (user, tags): (ArcStr, Vec<ArcStr>) = interning::with_table(&mut table, || {
+        #[derive(Deserialize)]
+        struct Pair(
+            #[serde(deserialize_with = "interning::deserialize")] ArcStr,
+            #[serde(deserialize_with = "interning::deserialize_vec")] Vec<ArcStr>,
+        );
+        let Pair(u, t) = bincode::deserialize(&bin).unwrap();
+        (u, t)
+    });
+    assert!(ArcStr::ptr_eq(&user, events[0].user.as_ref().unwrap()));
+    assert!(table.contains(&tags[0]));
+
+    // Nested calls use the innermost table, then go back to the outer one.
+    let mut outer = BTreeSet::new();
+    let mut inner = BTreeSet::new();
+    interning::with_table(&mut outer, || {
+        let e: Event = interning::with_table(&mut inner, || {
+            serde_json::from_str(r#"{"kind": "a", "user": null, "tags": [], "fields": {}}"#)
+        })
+        .unwrap();
+        let k: Event =
+            serde_json::from_str(r#"{"kind": "b", "user": null, "tags": [], "fields": {}}"#)
+                .unwrap();
+        assert_eq!((e.kind, k.kind), ("a".into(), "b".into()));
+    });
+    assert_eq!(inner.iter().collect::<Vec<_>>(), ["a"]);
+    assert_eq!(outer.iter().collect::<Vec<_>>(), ["b"]);
+
+    // Without an active table, these just deserialize normally.
+    let e: Event =
+        serde_json::from_str(r#"{"kind": "x", "user": "y", "tags": ["z"], "fields": {}}"#).unwrap();
+    assert_eq!(e.kind, "x");
+    assert_eq!(ArcStr::strong_count(&e.kind), Some(1));
+}
+
 #[test]
 fn test_loose_ends() {
     assert_eq!(ArcStr::default(), "");
@@ -263,6 +418,7 @@ fn test_inherent_overrides() {
 }
 
 #[test]
+#[allow(clippy::unnecessary_map_on_constructor)]
 fn test_froms_more() {
     let mut s = "asdf".to_string();
     {
@@ -325,3 +481,495 @@ fn test_froms_more() {
     let astr2 = ArcStr::from("foobar");
     assert!(ArcStr::ptr_eq(&astr2, &ArcStr::from(&astr2)))
 }
+
+#[test]
+fn test_literal_concat() {
+    const PREFIX: ArcStr = unsafe { arcstr::literal_arcstr!(b"/api/v1") };
+    const SUFFIX: &str = "/cpu";
+    const METRICS: ArcStr = arcstr::literal_concat!(PREFIX, "/metrics");
+    const CPU: ArcStr = arcstr::literal_concat!(METRICS, SUFFIX, "🏳️‍🌈");
+    assert_eq!(METRICS, "/api/v1/metrics");
+    assert_eq!(CPU, "/api/v1/metrics/cpu🏳️‍🌈");
+    assert!(ArcStr::is_static(&METRICS));
+    assert!(ArcStr::is_static(&CPU));
+
+    const EMPTY: ArcStr = arcstr::literal_concat!(ArcStr::new(), "");
+    assert_eq!(EMPTY, "");
+    assert!(ArcStr::is_static(&EMPTY));
+    assert_eq!(arcstr::literal_concat!(), "");
+    assert_eq!(arcstr::literal_concat!("a", PREFIX, "b",), "a/api/v1b");
+}
+
+#[test]
+#[should_panic(expected = "must be static")]
+fn test_concat_piece_nonstatic() {
+    // Not something the macro ever does, but it's reachable from safe code.
+    let s = ArcStr::from_owner(String::from("owned"));
+    let piece = arcstr::private_::ConcatPiece(core::mem::ManuallyDrop::new(s));
+    let _ = piece.as_bytes();
+}
+
+#[test]
+fn test_include_arcstr() {
+    const APACHE: ArcStr = arcstr::include_arcstr!("../LICENSE-APACHE");
+    assert!(ArcStr::is_static(&APACHE));
+    assert_eq!(APACHE, include_str!("../LICENSE-APACHE"));
+    let also_apache = unsafe { arcstr::literal_arcstr!(include_bytes!("../LICENSE-APACHE")) };
+    assert_eq!(APACHE, also_apache);
+}
+
+#[test]
+fn test_concat_join_repeat() {
+    assert_eq!(ArcStr::concat(&["a", "bc", "", "def"]), "abcdef");
+    assert_eq!(ArcStr::concat(&[String::from("🏳️‍🌈"), "x".into()]), "🏳️‍🌈x");
+    assert_eq!(ArcStr::concat::<&str>(&[]), "");
+    assert!(ArcStr::is_static(&ArcStr::concat(&["", ""])));
+
+    assert_eq!(ArcStr::join(&["a", "b", "c"], ", "), "a, b, c");
+    assert_eq!(ArcStr::join(vec![ArcStr::from("a")], ", "), "a");
+    assert_eq!(ArcStr::join(Vec::<&str>::new(), ", "), "");
+    assert_eq!(ArcStr::join(&["", ""], "-"), "-");
+
+    // A single non-empty `ArcStr` is returned as-is.
+    let a = ArcStr::from("abc");
+    assert!(ArcStr::ptr_eq(
+        &ArcStr::concat(&[ArcStr::new(), a.clone()]),
+        &a
+    ));
+    assert!(ArcStr::ptr_eq(&ArcStr::concat(&[&a]), &a));
+    assert!(ArcStr::ptr_eq(&ArcStr::join(vec![a.clone()], ", "), &a));
+    assert!(ArcStr::ptr_eq(
+        &[&a, &ArcStr::new()].iter().copied().collect(),
+        &a
+    ));
+    let copied = ArcStr::concat(&[a.clone(), ArcStr::from("d")]);
+    assert_eq!(copied, "abcd");
+
+    let s = ArcStr::from("ab");
+    assert_eq!(s.repeat(3), "ababab");
+    assert_eq!(s.repeat(0), "");
+    assert!(ArcStr::ptr_eq(&s, &s.repeat(1)));
+    assert_eq!(ArcStr::new().repeat(100), "");
+}
+
+#[test]
+#[should_panic]
+fn test_repeat_overflow() {
+    let _ = ArcStr::from("abc").repeat(usize::MAX / 2);
+}
+
+#[test]
+fn test_from_iter() {
+    let s: ArcStr = "a b c".split(' ').collect();
+    assert_eq!(s, "abc");
+    let s: ArcStr = "a b c".chars().rev().collect();
+    assert_eq!(s, "c b a");
+    // Chars of every width, with a size hint that's far too low.
+    let text = "aé€😀".repeat(100);
+    let s: ArcStr = text.chars().filter(|_| true).collect();
+    assert_eq!(s, text);
+    assert!(!s.is_ascii());
+    let s: ArcStr = std::iter::repeat('x').take(1000).collect();
+    assert_eq!(s, "x".repeat(1000));
+    assert!(s.is_ascii());
+    let s: ArcStr = std::iter::empty::<char>().collect();
+    assert!(ArcStr::is_static(&s));
+    let s: ArcStr = vec![String::from("x"), String::from("yz")]
+        .into_iter()
+        .collect();
+    assert_eq!(s, "xyz");
+
+    let foo = ArcStr::from("foo");
+    let s: ArcStr = vec![ArcStr::new(), foo.clone(), ArcStr::new()]
+        .into_iter()
+        .collect();
+    assert!(ArcStr::ptr_eq(&s, &foo));
+    let s: ArcStr = [foo.clone(), foo.clone()].iter().collect();
+    assert_eq!(s, "foofoo");
+    let s: ArcStr = std::iter::empty::<ArcStr>().collect();
+    assert_eq!(s, "");
+}
+
+#[test]
+fn test_transformations_clone_when_unchanged() {
+    macro_rules! check {
+        ($s:expr, $method:ident($($arg:expr),*), $std:ident) => {{
+            let s = ArcStr::from($s);
+            let got = s.$method($($arg),*);
+            assert_eq!(got, s.as_str().$std($($arg),*), "{}", $s);
+            assert_eq!(ArcStr::ptr_eq(&got, &s), got == s, "{}", $s);
+        }};
+    }
+    let inputs = [
+        "",
+        "abc",
+        "ABC",
+        "  abc \n",
+        "ὈΔΥΣΣΕΎΣ",
+        "ὀδυσσεύς",
+        "İstanbul",
+        "ΣΑΣ Σ",
+        "❤\n\t\"'",
+        "\u{301}abc",
+        "a\u{301}bc",
+    ];
+    for &input in &inputs {
+        check!(input, to_ascii_lowercase_arc(), to_ascii_lowercase);
+        check!(input, to_lowercase_arc(), to_lowercase);
+        check!(input, trim_arc(), trim);
+        check!(input, replace_arc("a", "b"), replace);
+        check!(input, replace_arc("a", "a"), replace);
+        check!(input, replace_arc("", "-"), replace);
+        check!(input, replace_arc("Σ", ""), replace);
+        check!(input, replace_arc("\n", "\\n"), replace);
+        let s = ArcStr::from(input);
+        let esc = s.escape_debug_arc();
+        assert_eq!(esc, s.escape_debug().to_string());
+        assert_eq!(ArcStr::ptr_eq(&esc, &s), esc == s);
+    }
+}
+
+#[test]
+fn test_append_prepend() {
+    let s = ArcStr::from("foo").append("bar").prepend("<").append(">");
+    assert_eq!(s, "<foobar>");
+    assert_eq!(ArcStr::strong_count(&s), Some(1));
+
+    let shared = s.clone();
+    let s2 = s.append("!").prepend("¡");
+    assert_eq!(s2, "¡<foobar>!");
+    assert_eq!(shared, "<foobar>");
+    assert_eq!(ArcStr::strong_count(&shared), Some(1));
+
+    let st = unsafe { arcstr::literal_arcstr!(b"static") };
+    assert_eq!(st.clone().append("!"), "static!");
+    assert_eq!(st.clone().prepend("!"), "!static");
+    assert!(ArcStr::ptr_eq(&st.clone().append(""), &st));
+    assert_eq!(ArcStr::new().append("x").prepend("y"), "yx");
+
+    let mut grown = ArcStr::new();
+    for i in 0..100 {
+        grown = grown.append(&i.to_string());
+    }
+    let expected = (0..100).map(|i| i.to_string()).collect::<String>();
+    assert_eq!(grown, expected);
+}
+
+#[test]
+fn test_from_owner() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    static DROPS: AtomicUsize = AtomicUsize::new(0);
+    struct Owner(Box<str>);
+    impl AsRef<str> for Owner {
+        fn as_ref(&self) -> &str {
+            &self.0
+        }
+    }
+    impl Drop for Owner {
+        fn drop(&mut self) {
+            DROPS.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    let boxed: Box<str> = "owned elsewhere".into();
+    let data = boxed.as_ptr();
+    let s = ArcStr::from_owner(Owner(boxed));
+    assert_eq!(s, "owned elsewhere");
+    assert_eq!(s.as_ptr(), data);
+    assert_eq!(ArcStr::strong_count(&s), Some(1));
+    assert!(!ArcStr::is_static(&s));
+
+    let s2 = s.clone();
+    assert!(ArcStr::ptr_eq(&s, &s2));
+    assert_eq!(ArcStr::strong_count(&s), Some(2));
+    drop(s);
+    assert_eq!(DROPS.load(Ordering::SeqCst), 0);
+    // Can't mutate (or grow) data we don't own.
+    let s2 = s2.try_unique().unwrap_err();
+    let s3 = s2.clone().append("!");
+    assert_eq!(s3, "owned elsewhere!");
+    assert_eq!(s2, "owned elsewhere");
+    drop(s2);
+    assert_eq!(DROPS.load(Ordering::SeqCst), 1);
+
+    // Owners that store their data inline work too.
+    struct Inline([u8; 3]);
+    impl AsRef<str> for Inline {
+        fn as_ref(&self) -> &str {
+            std::str::from_utf8(&self.0).unwrap()
+        }
+    }
+    let inline = ArcStr::from_owner(Inline(*b"abc"));
+    assert_eq!(inline, "abc");
+    assert_eq!(ArcStr::from_owner(String::new()), "");
+    assert!(ArcStr::is_static(&ArcStr::from_owner(String::new())));
+    let a = std::sync::Arc::<str>::from("shared");
+    assert_eq!(ArcStr::from_owner(a), "shared");
+}
+
+#[test]
+fn test_raw_data() {
+    use std::mem::size_of;
+    assert_eq!(size_of::<ArcStr>(), size_of::<usize>());
+    assert_eq!(size_of::<Option<ArcStr>>(), size_of::<usize>());
+
+    // Reads the bytes the same way C code would.
+    unsafe fn read(p: std::ptr::NonNull<u8>, len: usize) -> String {
+        String::from_utf8(std::slice::from_raw_parts(p.as_ptr(), len).to_vec()).unwrap()
+    }
+
+    const LIT: ArcStr = arcstr::literal_concat!("static data");
+    let owner = ArcStr::from_owner(String::from("external data"));
+    let external_ptr = owner.as_ptr();
+    for s in [ArcStr::from("heap data"), LIT, ArcStr::new(), owner] {
+        let (len, ptr) = (s.len(), s.as_ptr());
+        let expected = s.to_string();
+        let p = ArcStr::into_raw_data(s);
+        assert_eq!(p.as_ptr() as usize % 8, 0);
+        if ptr != external_ptr {
+            assert_eq!(p.as_ptr() as *const u8, ptr);
+        }
+        assert_eq!(unsafe { read(p, len) }, expected);
+        let s = unsafe { ArcStr::from_raw_data(p) };
+        assert_eq!(s, expected);
+        assert_eq!(s.as_ptr(), p.as_ptr() as *const u8);
+    }
+
+    let a = ArcStr::from("round trip");
+    let p = ArcStr::into_raw_data(a.clone());
+    assert_eq!(ArcStr::strong_count(&a), Some(2));
+    drop(unsafe { ArcStr::from_raw_data(p) });
+    assert_eq!(ArcStr::strong_count(&a), Some(1));
+}
+
+#[test]
+fn test_padded() {
+    fn check<const PAD: usize>(src: &str) {
+        let s = ArcStr::from_str_padded::<PAD>(src);
+        assert_eq!(s, src);
+        assert_eq!(s.len(), src.len());
+        assert_eq!(ArcStr::padding(&s), Some(PAD));
+        assert_eq!(s.as_ptr() as usize % ArcStr::PADDED_ALIGN, 0);
+        let tail = unsafe { std::slice::from_raw_parts(s.as_ptr().add(s.len()), PAD) };
+        assert!(tail.iter().all(|&b| b == 0));
+        assert!(!ArcStr::is_static(&s));
+        let c = s.clone();
+        assert_eq!(ArcStr::padding(&c), Some(PAD));
+        assert_eq!(ArcStr::strong_count(&s), Some(2));
+    }
+    for src in ["", "a", "hello, world", &"0123456789abcdef".repeat(20)] {
+        check::<0>(src);
+        check::<1>(src);
+        check::<16>(src);
+        check::<64>(src);
+        check::<1000>(src);
+    }
+
+    let s = ArcStr::from_str_padded::<32>("lower");
+    let mut u = s.try_unique().unwrap();
+    u.make_ascii_uppercase();
+    let s = u.into_arcstr();
+    assert_eq!(s, "LOWER");
+    assert_eq!(ArcStr::padding(&s), Some(32));
+
+    let s = s.prepend("<").append(">");
+    assert_eq!(s, "<LOWER>");
+    assert_eq!(ArcStr::padding(&s), None);
+    assert_eq!(ArcStr::padding(&ArcStr::new()), None);
+    assert_eq!(
+        ArcStr::padding(&ArcStr::from_owner(String::from("x"))),
+        None
+    );
+}
+
+#[test]
+fn test_cached_ascii() {
+    fn check(s: &ArcStr) {
+        assert_eq!(s.is_ascii(), s.as_str().is_ascii(), "{:?}", s);
+        assert_eq!(s.char_count(), s.chars().count(), "{:?}", s);
+        for n in 0..=s.len() {
+            assert_eq!(s.nth_char(n), s.chars().nth(n));
+        }
+    }
+    const LIT: ArcStr = unsafe { arcstr::literal_arcstr!(b"static") };
+    const LIT_UTF8: ArcStr = arcstr::literal_concat!(LIT, "·", "ß");
+    for s in &[
+        ArcStr::new(),
+        LIT,
+        LIT_UTF8,
+        ArcStr::from("abc"),
+        ArcStr::from("héllo"),
+        ArcStr::from_owner(String::from("owned")),
+        ArcStr::from_owner(String::from("ówned")),
+        ArcStr::from_str_padded::<8>("padded"),
+        ArcStr::from_str_padded::<8>("pàdded"),
+        ArcStr::from("ABC").to_ascii_lowercase_arc(),
+        ArcStr::from("ÀBC").to_lowercase_arc(),
+        ArcStr::from("a\tb").escape_debug_arc(),
+    ] {
+        check(s);
+    }
+    assert!(!LIT_UTF8.is_ascii());
+    assert_eq!(LIT_UTF8.char_count(), 8);
+
+    // Appending in place keeps the flag up to date.
+    let s = ArcStr::from("abc");
+    let s = s.append("def");
+    check(&s);
+    assert!(s.is_ascii());
+    let s = s.append("é");
+    check(&s);
+    assert!(!s.is_ascii());
+    let s = ArcStr::from("abc").prepend("ü");
+    check(&s);
+    let s = s.append("x");
+    assert!(!s.is_ascii());
+
+    // As does mutation through `UniqueArcStr`.
+    let mut u = ArcStr::from("abc").try_unique().unwrap();
+    u.make_ascii_uppercase();
+    let s = u.into_arcstr();
+    check(&s);
+    assert_eq!(s.nth_char(2), Some('C'));
+}
+
+#[cfg(feature = "bytes")]
+#[test]
+fn test_bytes() {
+    use bytes::Bytes;
+    use std::convert::TryFrom;
+
+    let s = ArcStr::from("shared with bytes");
+    let b = Bytes::from(s.clone());
+    assert_eq!(&b[..], b"shared with bytes");
+    assert_eq!(b.as_ptr(), s.as_ptr());
+    assert_eq!(ArcStr::strong_count(&s), Some(2));
+    drop(b);
+    assert_eq!(ArcStr::strong_count(&s), Some(1));
+
+    let st = unsafe { arcstr::literal_arcstr!(b"static") };
+    let b = Bytes::from(st.clone());
+    assert_eq!(b.as_ptr(), st.as_ptr());
+    assert_eq!(Bytes::from(ArcStr::new()), Bytes::new());
+
+    let b = Bytes::from(String::from("from bytes"));
+    let ptr = b.as_ptr();
+    let s = ArcStr::try_from(b.slice(5..)).unwrap();
+    assert_eq!(s, "bytes");
+    assert_eq!(s.as_ptr(), ptr.wrapping_add(5));
+    drop(b);
+    assert_eq!(s, "bytes");
+
+    assert!(ArcStr::try_from(Bytes::from_static(b"\xff")).is_err());
+    assert_eq!(ArcStr::try_from(Bytes::new()).unwrap(), "");
+}
+
+#[cfg(feature = "rkyv")]
+#[test]
+fn test_rkyv() {
+    use rkyv::rancor::Error;
+
+    let a = ArcStr::from("shared");
+    let b = ArcStr::from("shared");
+    let strs = vec![a.clone(), b.clone(), a.clone(), ArcStr::new(), a];
+    let bytes = rkyv::to_bytes::<Error>(&strs).unwrap();
+    let archived =
+        rkyv::access::<rkyv::vec::ArchivedVec<arcstr::ArchivedArcStr>, Error>(&bytes).unwrap();
+    assert_eq!(archived.len(), 5);
+    assert_eq!(&*archived[0], "shared");
+    assert_eq!(archived[1], b);
+    // Clones of one `ArcStr` are stored once; equal but distinct strings aren't.
+    assert_eq!(archived[0].as_ptr(), archived[2].as_ptr());
+    assert_ne!(archived[0].as_ptr(), archived[1].as_ptr());
+
+    let out: Vec<ArcStr> = rkyv::deserialize::<Vec<ArcStr>, Error>(archived).unwrap();
+    assert_eq!(out, strs);
+    assert!(ArcStr::ptr_eq(&out[0], &out[2]));
+    assert!(ArcStr::ptr_eq(&out[0], &out[4]));
+    assert!(!ArcStr::ptr_eq(&out[0], &out[1]));
+    assert_eq!(ArcStr::strong_count(&out[0]), Some(3));
+    assert!(ArcStr::is_static(&out[3]));
+
+    // Static strings come back static if the deserializer knows about them.
+    arcstr::static_set! {
+        static KNOWN = ["static"];
+    }
+    let strs = vec![
+        arcstr::literal_concat!("static"),
+        ArcStr::from("static"),
+        arcstr::literal_concat!("unknown"),
+    ];
+    let bytes = rkyv::to_bytes::<Error>(&strs).unwrap();
+    let archived =
+        rkyv::access::<rkyv::vec::ArchivedVec<arcstr::ArchivedArcStr>, Error>(&bytes).unwrap();
+    assert!(archived[0].is_static());
+    assert!(!archived[1].is_static());
+    let mut pool = arcstr::StaticSetPool::new(&KNOWN);
+    let out: Vec<ArcStr> =
+        rkyv::api::deserialize_using::<_, _, Error>(archived, &mut pool).unwrap();
+    assert_eq!(out, strs);
+    assert!(ArcStr::ptr_eq(&out[0], &KNOWN.as_slice()[0]));
+    assert!(!ArcStr::is_static(&out[1]));
+    assert!(!ArcStr::is_static(&out[2]));
+
+    // Validation catches a bad flag (and anything else that's invalid).
+    let flag = (&archived[0] as *const _ as usize - bytes.as_ptr() as usize)
+        + std::mem::size_of::<rkyv::rc::ArchivedRc<str, arcstr::ArcStrFlavor>>();
+    let mut bad = bytes.clone();
+    assert_eq!(bad[flag], 1);
+    bad[flag] = 2;
+    assert!(rkyv::access::<rkyv::vec::ArchivedVec<arcstr::ArchivedArcStr>, Error>(&bad).is_err());
+}
+
+arcstr::static_set! {
+    static KEYWORDS = [
+        "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum",
+        "extern", "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move",
+        "mut", "pub", "ref", "return", "self", "Self", "static", "struct", "super", "trait",
+        "true", "type", "unsafe", "use", "where", "while", "",
+    ];
+}
+
+#[test]
+fn test_static_set() {
+    assert_eq!(KEYWORDS.len(), 39);
+    for (i, k) in KEYWORDS.as_slice().iter().enumerate() {
+        assert!(ArcStr::is_static(k));
+        let found = KEYWORDS.get(k).unwrap();
+        assert!(ArcStr::ptr_eq(found, &KEYWORDS.as_slice()[i]));
+        let s = ArcStr::from_known(&String::from(k.as_str()), &KEYWORDS);
+        assert!(ArcStr::ptr_eq(&s, found));
+    }
+    for miss in &["a", "asy", "asyncc", "SELF", "struct ", "loops", "yield"] {
+        assert!(!KEYWORDS.contains(miss), "{}", miss);
+        let s = ArcStr::from_known(miss, &KEYWORDS);
+        assert_eq!(s, *miss);
+        assert!(!ArcStr::is_static(&s));
+    }
+
+    const EMPTY: arcstr::StaticSet<0> = arcstr::static_set![];
+    assert!(EMPTY.is_empty());
+    assert_eq!(EMPTY.get(""), None);
+    assert_eq!(ArcStr::from_known("x", &EMPTY), "x");
+
+    const PREFIX: ArcStr = arcstr::literal_concat!("/api");
+    let paths: arcstr::StaticSet<2> =
+        arcstr::static_set![PREFIX, arcstr::literal_concat!(PREFIX, "/v1")];
+    assert_eq!(paths.get("/api/v1").unwrap(), "/api/v1");
+    assert_eq!(format!("{:?}", paths), r#"{"/api", "/api/v1"}"#);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_static_set_serde() {
+    use serde::de::DeserializeSeed;
+    let mut de = serde_json::Deserializer::from_str(r#""while""#);
+    let s = KEYWORDS.deserialize(&mut de).unwrap();
+    assert!(ArcStr::ptr_eq(&s, KEYWORDS.get("while").unwrap()));
+    let mut de = serde_json::Deserializer::from_str(r#""whilst""#);
+    let s = KEYWORDS.deserialize(&mut de).unwrap();
+    assert_eq!(s, "whilst");
+    assert!(!ArcStr::is_static(&s));
+    let mut de = serde_json::Deserializer::from_str("5");
+    assert!(KEYWORDS.deserialize(&mut de).is_err());
+}
@@ -0,0 +1,69 @@
+use arcstr::{ArcStr, ArcStrArena};
+
+#[test]
+fn test_arena() {
+    let mut arena = ArcStrArena::new();
+    let words: Vec<String> = (0..20_000).map(|i| format!("word{}", i)).collect();
+    let strs: Vec<ArcStr> = words.iter().map(|w| arena.alloc(w)).collect();
+    for (w, s) in words.iter().zip(&strs) {
+        assert_eq!(s, w);
+        assert_eq!(s.as_ptr() as usize % 8, 0);
+        assert!(s.is_ascii());
+    }
+    // Neighbours are packed into the same block.
+    let gap = strs[1].as_ptr() as usize - strs[0].as_ptr() as usize;
+    assert!(gap < 32, "{}", gap);
+
+    let kept = strs[12_345].clone();
+    drop(strs);
+    drop(arena);
+    // The block outlives both the arena and the other strings in it.
+    assert_eq!(kept, "word12345");
+    assert_eq!(ArcStr::strong_count(&kept), None);
+    let c = kept.clone();
+    assert_eq!(ArcStr::strong_count(&kept), None);
+    drop(kept);
+    assert_eq!(c, "word12345");
+}
+
+#[test]
+fn test_arena_special_cases() {
+    let mut arena = ArcStrArena::default();
+    assert!(ArcStr::is_static(&arena.alloc("")));
+    let big = "x".repeat(100_000);
+    let b = arena.alloc(&big);
+    assert_eq!(b, big);
+    let s = arena.alloc("héllo");
+    assert!(!s.is_ascii());
+    assert_eq!(s.char_count(), 5);
+
+    // Arena strings can't be grown or mutated in place, so they're copied.
+    let t = s.clone().append(" world");
+    assert_eq!(t, "héllo world");
+    assert_eq!(s, "héllo");
+    // ... even once nothing else is using their block.
+    let mut small = ArcStrArena::new();
+    let a = small.alloc("abc");
+    drop(small);
+    let a = a.try_unique().unwrap_err();
+    assert_eq!(a.append("d"), "abcd");
+    let p = ArcStr::into_raw_data(arena.alloc("raw"));
+    assert_eq!(unsafe { ArcStr::from_raw_data(p) }, "raw");
+}
+
+#[test]
+fn test_arena_threads() {
+    let mut arena = ArcStrArena::new();
+    let strs: Vec<ArcStr> = (0..10_000).map(|i| arena.alloc(&i.to_string())).collect();
+    let handles: Vec<_> = strs
+        .chunks(1000)
+        .map(|c| {
+            let c = c.to_vec();
+            std::thread::spawn(move || c.iter().map(|s| s.len()).sum::<usize>())
+        })
+        .collect();
+    drop(strs);
+    drop(arena);
+    let total: usize = handles.into_iter().map(|h| h.join().unwrap()).sum();
+    assert_eq!(total, (0..10_000).map(|i: i32| i.to_string().len()).sum());
+}
@@ -0,0 +1,105 @@
+use arcstr::{ArcStr, ArcStrList};
+use std::collections::HashSet;
+
+#[test]
+fn test_list() {
+    let words = ["zero", "", "two", "thrée", "four"];
+    let list: ArcStrList = words.iter().collect();
+    assert_eq!(list.len(), 5);
+    assert!(!list.is_empty());
+    for (i, w) in words.iter().enumerate() {
+        assert_eq!(list.get(i), Some(*w));
+        assert_eq!(&list[i], *w);
+        assert_eq!(list.get_arc(i).unwrap(), *w);
+    }
+    assert_eq!(list.get(5), None);
+    assert_eq!(list.get_arc(5), None);
+    assert_eq!(list, words[..]);
+    assert_eq!(
+        list.iter().rev().collect::<Vec<_>>(),
+        ["four", "thrée", "two", "", "zero"]
+    );
+    let mut it = list.iter();
+    assert_eq!(it.len(), 5);
+    assert_eq!((it.next(), it.next_back()), (Some("zero"), Some("four")));
+    assert_eq!(it.len(), 3);
+    assert_eq!(
+        format!("{:?}", list),
+        r#"["zero", "", "two", "thrée", "four"]"#
+    );
+
+    let c = list.clone();
+    assert!(ArcStrList::ptr_eq(&c, &list));
+    drop(list);
+    assert_eq!(&c[3], "thrée");
+
+    let owned: ArcStrList = vec![String::from("a"), String::from("b")]
+        .into_iter()
+        .collect();
+    assert_eq!(owned, vec!["a", "b"]);
+    let s: ArcStr = owned.get_arc(0).unwrap();
+    drop(owned);
+    assert_eq!(s, "a");
+}
+
+#[test]
+fn test_empty() {
+    let e = ArcStrList::new();
+    assert!(e.is_empty());
+    assert_eq!(e.get(0), None);
+    assert_eq!(e.iter().next(), None);
+    let d: ArcStrList = std::iter::empty::<&str>().collect();
+    assert!(ArcStrList::ptr_eq(&e, &d));
+    assert_eq!(e, ArcStrList::default());
+    let _ = e.clone();
+
+    let blanks: ArcStrList = ["", ""].iter().collect();
+    assert_eq!(blanks.len(), 2);
+    assert_eq!(blanks, ["", ""][..]);
+    assert_ne!(blanks, e);
+}
+
+#[test]
+#[should_panic]
+fn test_index_oob() {
+    let list: ArcStrList = ["a"].iter().collect();
+    let _ = &list[1];
+}
+
+#[test]
+fn test_traits() {
+    let a: ArcStrList = ["x", "y"].iter().collect();
+    let b: ArcStrList = ["x", "y"].iter().collect();
+    let c: ArcStrList = ["x", "z"].iter().collect();
+    assert_eq!(a, b);
+    assert!(a < c);
+    let mut set = HashSet::new();
+    set.insert(a);
+    assert!(set.contains(&b));
+    assert!(!set.contains(&c));
+
+    let threads: Vec<_> = (0..4)
+        .map(|_| {
+            let b = b.clone();
+            std::thread::spawn(move || b.iter().map(str::len).sum::<usize>())
+        })
+        .collect();
+    for t in threads {
+        assert_eq!(t.join().unwrap(), 2);
+    }
+}
+
+#[test]
+#[cfg(feature = "serde")]
+fn test_serde() {
+    let list: ArcStrList = serde_json::from_str(r#"["plain", "esc\"aped", ""]"#).unwrap();
+    assert_eq!(list, ["plain", "esc\"aped", ""][..]);
+    assert_eq!(
+        serde_json::to_string(&list).unwrap(),
+        r#"["plain","esc\"aped",""]"#
+    );
+    let bin = bincode::serialize(&list).unwrap();
+    let back: ArcStrList = bincode::deserialize(&bin).unwrap();
+    assert_eq!(back, list);
+    assert!(serde_json::from_str::<ArcStrList>(r#"["a", 1]"#).is_err());
+}
@@ -0,0 +1,61 @@
+use arcstr::{literal_concat, ArcStr, ArcStrRef};
+use std::collections::HashSet;
+
+#[test]
+fn test_borrow_ref() {
+    assert_eq!(
+        std::mem::size_of::<ArcStrRef<'_>>(),
+        std::mem::size_of::<usize>()
+    );
+    assert_eq!(
+        std::mem::size_of::<Option<ArcStrRef<'_>>>(),
+        std::mem::size_of::<usize>()
+    );
+
+    let s = ArcStr::from("hello");
+    let r = s.borrow_ref();
+    let copies = [r; 4];
+    assert_eq!(ArcStr::strong_count(&s), Some(1));
+    assert!(copies.iter().all(|c| *c == "hello"));
+    assert!(ArcStr::ptr_eq(r.as_arcstr(), &s));
+    assert!(ArcStrRef::ptr_eq(r, ArcStrRef::from(&s)));
+    assert!(!ArcStrRef::ptr_eq(r, ArcStr::from("hello").borrow_ref()));
+
+    let up = r.upgrade();
+    assert_eq!(ArcStr::strong_count(&s), Some(2));
+    assert!(ArcStr::ptr_eq(&up, &s));
+    drop(up);
+    let up: ArcStr = r.into();
+    assert_eq!(ArcStr::strong_count(&s), Some(2));
+    drop(up);
+    assert_eq!(ArcStr::strong_count(&s), Some(1));
+}
+
+#[test]
+fn test_static_and_lifetimes() {
+    let s = literal_concat!("static");
+    let r = s.borrow_ref();
+    assert!(ArcStr::is_static(&r.upgrade()));
+
+    let owned = ArcStr::from("outlives the ref");
+    let text: &str = {
+        let r = owned.borrow_ref();
+        r.as_str()
+    };
+    assert_eq!(text, "outlives the ref");
+}
+
+#[test]
+fn test_traits() {
+    let a = ArcStr::from("a");
+    let b = ArcStr::from("b");
+    let (ra, rb) = (a.borrow_ref(), b.borrow_ref());
+    assert!(ra < rb);
+    assert_eq!(ra, ArcStr::from("a").borrow_ref());
+    assert_eq!(ra, a);
+    assert_eq!("b", rb);
+    assert_eq!(format!("{} {:?}", ra, rb), "a \"b\"");
+    let set: HashSet<ArcStrRef<'_>> = [ra, rb, ra].iter().copied().collect();
+    assert_eq!(set.len(), 2);
+    assert!(set.contains("a"));
+}
@@ -0,0 +1,115 @@
+use arcstr::{
+    literal_arcstr, literal_concat, ArcStr, AsciiCaseInsensitive, AsciiCaseInsensitiveStr,
+    UniCaseArcStr, UniCaseStr,
+};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+
+fn hash_of<T: Hash + ?Sized>(v: &T) -> u64 {
+    let mut h = DefaultHasher::new();
+    v.hash(&mut h);
+    h.finish()
+}
+
+const CONTENT_TYPE: AsciiCaseInsensitive =
+    AsciiCaseInsensitive::new(unsafe { literal_arcstr!(b"Content-Type") });
+
+#[test]
+fn test_ascii_case_insensitive() {
+    let a = AsciiCaseInsensitive::from("CONTENT-type");
+    assert_eq!(a, CONTENT_TYPE);
+    assert_eq!(hash_of(&a), hash_of(&CONTENT_TYPE));
+    assert_eq!(CONTENT_TYPE.as_str(), "Content-Type");
+    assert_eq!(CONTENT_TYPE.to_string(), "Content-Type");
+    assert_eq!(format!("{:?}", CONTENT_TYPE), r#""Content-Type""#);
+    assert!(ArcStr::is_static(CONTENT_TYPE.as_arcstr()));
+
+    assert_eq!(a, "content-type");
+    assert_eq!("Content-TYPE", a);
+    assert_ne!(a, "content_type");
+    // Only ASCII is folded.
+    assert_ne!(AsciiCaseInsensitive::from("É"), "é");
+
+    let (lo_a, up_a) = (
+        AsciiCaseInsensitive::from("a"),
+        AsciiCaseInsensitive::from("A"),
+    );
+    let (lo_b, up_b) = (
+        AsciiCaseInsensitive::from("b"),
+        AsciiCaseInsensitive::from("B"),
+    );
+    let ab = AsciiCaseInsensitive::from("ab");
+    assert!(lo_a < up_b && up_a < lo_b);
+    assert!(ab > up_a && ab < up_b);
+
+    let b = AsciiCaseInsensitiveStr::new("CONTENT-TYPE");
+    assert_eq!(hash_of(b), hash_of(&a));
+    assert_eq!(ArcStr::from(a.clone()), "CONTENT-type");
+    assert_eq!(a.into_arcstr(), "CONTENT-type");
+}
+
+#[test]
+fn test_ascii_map_lookup() {
+    let mut headers = HashMap::new();
+    headers.insert(CONTENT_TYPE, 1);
+    headers.insert(AsciiCaseInsensitive::from("Accept"), 2);
+    headers.insert(AsciiCaseInsensitive::from("ACCEPT"), 3);
+    assert_eq!(headers.len(), 2);
+    assert_eq!(headers[AsciiCaseInsensitiveStr::new("accept")], 3);
+    assert_eq!(
+        headers.get(AsciiCaseInsensitiveStr::new("content-TYPE")),
+        Some(&1)
+    );
+    assert_eq!(headers.get(AsciiCaseInsensitiveStr::new("Host")), None);
+    let (k, _) = headers
+        .get_key_value(AsciiCaseInsensitiveStr::new("accept"))
+        .unwrap();
+    // The first key inserted is kept.
+    assert_eq!(k.as_str(), "Accept");
+
+    let mut sorted = BTreeMap::new();
+    for k in ["b", "A", "c", "B"] {
+        sorted.insert(AsciiCaseInsensitive::from(k), ());
+    }
+    let keys: Vec<&str> = sorted.keys().map(|k| k.as_str()).collect();
+    assert_eq!(keys, ["A", "b", "c"]);
+    assert!(sorted.contains_key(AsciiCaseInsensitiveStr::new("C")));
+}
+
+#[test]
+fn test_unicase() {
+    const STRASSE: UniCaseArcStr = UniCaseArcStr::new(literal_concat!("Straße"));
+    let upper = UniCaseArcStr::from("STRASSE");
+    assert_eq!(STRASSE, upper);
+    assert_eq!(hash_of(&STRASSE), hash_of(&upper));
+    assert_eq!(STRASSE.as_str(), "Straße");
+
+    assert_eq!(UniCaseArcStr::from("ΣΊΣΥΦΟΣ"), "σίσυφος");
+    assert_eq!(UniCaseArcStr::from("ΣΊΣΥΦΟΣ"), "σίσυφοσ");
+    // Kelvin sign.
+    assert_eq!(UniCaseArcStr::from("\u{212a}"), "k");
+    assert_eq!(
+        hash_of(&UniCaseArcStr::from("\u{212a}")),
+        hash_of(UniCaseStr::new("K"))
+    );
+    assert_eq!(UniCaseArcStr::from("Hello"), "hELLO");
+    assert_ne!(UniCaseArcStr::from("Hello"), "hELL");
+
+    let (e_acute, e_circ) = (UniCaseArcStr::from("é"), UniCaseArcStr::from("Ê"));
+    assert!(e_acute < e_circ);
+    assert_eq!(
+        UniCaseArcStr::from("ß").cmp(&UniCaseArcStr::from("SS")),
+        std::cmp::Ordering::Equal
+    );
+
+    let mut set = HashSet::new();
+    set.insert(UniCaseArcStr::from("Ärger"));
+    set.insert(UniCaseArcStr::from("äRGER"));
+    assert_eq!(set.len(), 1);
+    assert!(set.contains(UniCaseStr::new("ÄRGER")));
+    assert!(!set.contains(UniCaseStr::new("arger")));
+
+    let owned: UniCaseArcStr = UniCaseStr::new("Abc").to_owned();
+    assert_eq!(owned.as_str(), "Abc");
+}
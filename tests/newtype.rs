@@ -0,0 +1,135 @@
+use arcstr::{arcstr_newtype, literal_concat, ArcStr};
+use std::collections::HashMap;
+use std::convert::TryFrom;
+
+#[derive(Debug, PartialEq)]
+pub struct BadUserId;
+
+impl std::fmt::Display for BadUserId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("user ids must be non-empty and alphanumeric")
+    }
+}
+
+fn check_user_id(s: &str) -> Result<(), BadUserId> {
+    if !s.is_empty() && s.bytes().all(|b| b.is_ascii_alphanumeric()) {
+        Ok(())
+    } else {
+        Err(BadUserId)
+    }
+}
+
+arcstr_newtype! {
+    /// A user id.
+    pub struct UserId(ArcStr) where validate = check_user_id, error = BadUserId;
+}
+
+#[derive(Debug, PartialEq)]
+pub struct BadTag(u8);
+
+const fn check_tag(s: &str) -> Result<(), BadTag> {
+    let b = s.as_bytes();
+    let mut i = 0;
+    while i < b.len() {
+        if !b[i].is_ascii_lowercase() {
+            return Err(BadTag(b[i]));
+        }
+        i += 1;
+    }
+    Ok(())
+}
+
+arcstr_newtype! {
+    pub(crate) struct Tag(ArcStr) where validate = const check_tag, error = BadTag,;
+}
+
+const RUST: Tag = Tag::from_static(literal_concat!("rust"));
+
+#[test]
+fn test_newtype_validation() {
+    let s = ArcStr::from("bob");
+    let id = UserId::new(s.clone()).unwrap();
+    assert!(ArcStr::ptr_eq(id.as_arcstr(), &s));
+    assert_eq!(UserId::new("b b"), Err(BadUserId));
+    assert_eq!(UserId::try_from("bob"), Ok(id.clone()));
+    assert_eq!(UserId::try_from(String::from("")), Err(BadUserId));
+    assert_eq!(UserId::try_from(s.clone()).unwrap(), id);
+    assert_eq!("bob".parse::<UserId>().unwrap(), id);
+    assert_eq!("b-b".parse::<UserId>(), Err(BadUserId));
+
+    assert_eq!(Tag::new("Rust"), Err(BadTag(b'R')));
+    assert_eq!(Tag::new("rust").unwrap(), RUST);
+    assert!(ArcStr::is_static(RUST.as_arcstr()));
+}
+
+#[test]
+fn test_newtype_forwarding() {
+    let id = UserId::new("alice").unwrap();
+    assert_eq!(id, "alice");
+    assert_eq!("alice", id);
+    assert_eq!(*"alice", id);
+    assert_eq!(id, ArcStr::from("alice"));
+    assert_eq!(ArcStr::from("alice"), id);
+    assert_eq!(id, String::from("alice"));
+    assert_eq!(String::from("alice"), id);
+    assert_eq!(&id[1..3], "li");
+    assert_eq!(&id[..], "alice");
+    assert_eq!(id.to_uppercase(), "ALICE");
+    assert_eq!(format!("{} {:?}", id, id), r#"alice "alice""#);
+    let bytes: &[u8] = id.as_ref();
+    assert_eq!(bytes, b"alice");
+    assert!(id < UserId::new("bob").unwrap());
+
+    let mut m = HashMap::new();
+    m.insert(id.clone(), 1);
+    assert_eq!(m.get("alice"), Some(&1));
+
+    let s: ArcStr = id.into();
+    assert_eq!(ArcStr::strong_count(&s), Some(2));
+}
+
+#[test]
+#[should_panic(expected = "requires a static `ArcStr`")]
+fn test_newtype_from_static_nonstatic() {
+    let _ = Tag::from_static(ArcStr::from("abc"));
+}
+
+#[test]
+#[should_panic(expected = "invalid `Tag` passed to `from_static`")]
+fn test_newtype_from_static_invalid() {
+    let _ = Tag::from_static(literal_concat!("ABC"));
+}
+
+#[cfg(feature = "serde")]
+mod serde_types {
+    use super::*;
+
+    arcstr_newtype! {
+        pub struct SerdeUserId(ArcStr) where validate = check_user_id, error = BadUserId, serde;
+    }
+
+    arcstr_newtype! {
+        pub struct SerdeTag(ArcStr) where validate = const check_tag, error = BadTag, serde,;
+    }
+
+    #[test]
+    fn test_newtype_serde() {
+        let id = SerdeUserId::new("carol").unwrap();
+        assert_eq!(serde_json::to_string(&id).unwrap(), r#""carol""#);
+        assert_eq!(
+            serde_json::from_str::<SerdeUserId>(r#""carol""#).unwrap(),
+            id
+        );
+        let e = serde_json::from_str::<SerdeUserId>(r#""ca rol""#).unwrap_err();
+        assert!(
+            e.to_string().starts_with("user ids must be non-empty"),
+            "{}",
+            e
+        );
+        // `BadTag` isn't `Display`.
+        let e = serde_json::from_str::<SerdeTag>(r#""Rust""#).unwrap_err();
+        assert!(e.to_string().starts_with("invalid `SerdeTag`"), "{}", e);
+        let tag = SerdeTag::from_static(literal_concat!("rust"));
+        assert_eq!(serde_json::from_str::<SerdeTag>(r#""rust""#).unwrap(), tag);
+    }
+}
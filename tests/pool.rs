@@ -0,0 +1,84 @@
+#![cfg(feature = "pool")]
+use arcstr::{pool, ArcStr};
+
+#[test]
+fn test_reuse() {
+    pool::trim();
+    let a = ArcStr::from("hello");
+    let p = a.as_ptr();
+    drop(a);
+    assert!(pool::cached_bytes() > 0);
+    // Same size class, so it gets the same allocation back.
+    let b = ArcStr::from("world!");
+    assert_eq!(b.as_ptr(), p);
+    assert_eq!(b, "world!");
+    assert_eq!(pool::cached_bytes(), 0);
+
+    // Long strings aren't pooled.
+    drop(ArcStr::from("x".repeat(1000)));
+    assert_eq!(pool::cached_bytes(), 0);
+    // Nor are static or external ones.
+    drop(arcstr::literal_concat!("static"));
+    drop(ArcStr::from_owner(String::from("owned")));
+    assert_eq!(pool::cached_bytes(), 0);
+}
+
+#[test]
+fn test_trim_and_bound() {
+    pool::trim();
+    let strings: Vec<ArcStr> = (0..1000).map(|i| ArcStr::from(format!("s{}", i))).collect();
+    drop(strings);
+    let cached = pool::cached_bytes();
+    assert!(cached > 0);
+    // The cache is bounded, so most of these were really freed.
+    assert!(cached < 1000 * 32, "{}", cached);
+    pool::trim();
+    assert_eq!(pool::cached_bytes(), 0);
+}
+
+#[test]
+fn test_grow_in_place() {
+    pool::trim();
+    let s = ArcStr::from("a");
+    let p = s.as_ptr();
+    // Still fits in the rounded-up allocation.
+    let s = s.append("bc");
+    assert_eq!(s.as_ptr(), p);
+    assert_eq!(s, "abc");
+    let s = s.append(&"d".repeat(500));
+    assert_eq!(s.len(), 503);
+    let s = s.prepend("z");
+    assert!(s.starts_with("zabcd"));
+}
+
+#[test]
+fn test_cross_thread() {
+    pool::trim();
+    let strings: Vec<ArcStr> = (0..100)
+        .map(|i| ArcStr::from(format!("{:08}", i)))
+        .collect();
+    std::thread::spawn(move || {
+        // These end up in this thread's cache, which is freed when it exits.
+        drop(strings);
+        assert!(pool::cached_bytes() > 0);
+    })
+    .join()
+    .unwrap();
+    assert_eq!(pool::cached_bytes(), 0);
+    let strings: Vec<ArcStr> = (0..100)
+        .map(|i| ArcStr::from(format!("{:08}", i)))
+        .collect();
+    let handles: Vec<_> = strings
+        .into_iter()
+        .map(|s| {
+            std::thread::spawn(move || {
+                let t = s.clone().append("!");
+                drop(s);
+                t
+            })
+        })
+        .collect();
+    for (i, h) in handles.into_iter().enumerate() {
+        assert_eq!(h.join().unwrap(), format!("{:08}!", i));
+    }
+}
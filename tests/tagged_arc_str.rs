@@ -0,0 +1,82 @@
+use arcstr::{literal_concat, ArcStr, TaggedArcStr};
+use std::collections::HashSet;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Kind {
+    Ident = 0,
+    Keyword = 1,
+    Literal = 2,
+}
+
+impl Kind {
+    fn from_tag(t: u8) -> Self {
+        match t {
+            0 => Kind::Ident,
+            1 => Kind::Keyword,
+            2 => Kind::Literal,
+            _ => unreachable!(),
+        }
+    }
+}
+
+#[test]
+fn test_tags() {
+    let a = ArcStr::from("while");
+    let mut t = TaggedArcStr::<2>::new(a.clone(), Kind::Keyword as u8);
+    assert_eq!(Kind::from_tag(t.tag()), Kind::Keyword);
+    assert_eq!(&*t, "while");
+    assert_eq!(ArcStr::strong_count(&a), Some(2));
+
+    for tag in [Kind::Literal, Kind::Ident, Kind::Keyword] {
+        t.set_tag(tag as u8);
+        assert_eq!(Kind::from_tag(t.tag()), tag);
+        assert_eq!(t.as_str(), "while");
+        assert!(ArcStr::ptr_eq(&t.as_arcstr(), &a));
+    }
+
+    let t2 = t.clone();
+    assert_eq!(ArcStr::strong_count(&a), Some(3));
+    assert_eq!(t, t2);
+    drop(t);
+    assert_eq!(ArcStr::strong_count(&a), Some(2));
+    let (b, tag) = t2.into_parts();
+    assert_eq!(tag, Kind::Keyword as u8);
+    assert!(ArcStr::ptr_eq(&a, &b));
+    drop(b);
+    assert_eq!(ArcStr::strong_count(&a), Some(1));
+}
+
+#[test]
+fn test_all_widths() {
+    let s = literal_concat!("static");
+    assert_eq!(TaggedArcStr::<0>::MAX_TAG, 0);
+    assert_eq!(TaggedArcStr::<3>::MAX_TAG, 7);
+    for tag in 0..=7 {
+        let t = TaggedArcStr::<3>::new(s.clone(), tag);
+        assert_eq!(t.tag(), tag);
+        assert!(ArcStr::is_static(&t.as_arcstr()));
+        assert_eq!(t.into_arcstr(), "static");
+    }
+    let t = TaggedArcStr::<0>::from(ArcStr::from("x"));
+    assert_eq!(t.tag(), 0);
+    assert_eq!(format!("{:?}", t), r#"TaggedArcStr { str: "x", tag: 0 }"#);
+}
+
+#[test]
+fn test_eq_hash() {
+    let a = TaggedArcStr::<1>::new(ArcStr::from("a"), 0);
+    let b = TaggedArcStr::<1>::new(ArcStr::from("a"), 1);
+    assert_ne!(a, b);
+    assert!(a < b);
+    let mut set = HashSet::new();
+    set.insert(a.clone());
+    set.insert(b);
+    set.insert(a);
+    assert_eq!(set.len(), 2);
+}
+
+#[test]
+#[should_panic]
+fn test_tag_too_large() {
+    let _ = TaggedArcStr::<2>::new(ArcStr::from("a"), 4);
+}
@@ -0,0 +1,44 @@
+use arcstr::{ArcStr, UniqueArcStr};
+
+#[test]
+fn test_unique_mutation() {
+    let mut s = UniqueArcStr::from("hello, world");
+    s.make_ascii_uppercase();
+    assert_eq!(s, "HELLO, WORLD");
+    s.as_mut_str()[..5].make_ascii_lowercase();
+    assert_eq!(s.as_str(), "hello, WORLD");
+
+    let a = s.into_arcstr();
+    assert_eq!(a, "hello, WORLD");
+    assert_eq!(ArcStr::strong_count(&a), Some(1));
+
+    let mut empty = UniqueArcStr::new();
+    empty.make_ascii_uppercase();
+    assert_eq!(&*empty, "");
+    assert!(ArcStr::is_static(&ArcStr::from(empty)));
+    assert_eq!(UniqueArcStr::default(), UniqueArcStr::from(String::new()));
+}
+
+#[test]
+fn test_try_unique() {
+    let a = ArcStr::from("abc");
+    let b = a.clone();
+    let a = a.try_unique().unwrap_err();
+    drop(b);
+    let mut u = a.try_unique().unwrap();
+    u.make_ascii_uppercase();
+    let a = ArcStr::from(u);
+    assert_eq!(a, "ABC");
+
+    let st = unsafe { arcstr::literal_arcstr!(b"static") };
+    let st = st.try_unique().unwrap_err();
+    assert_eq!(st, "static");
+    assert!(ArcStr::new().try_unique().is_err());
+
+    let t = std::thread::spawn({
+        let a = a.clone();
+        move || drop(a)
+    });
+    t.join().unwrap();
+    assert!(a.try_unique().is_ok());
+}